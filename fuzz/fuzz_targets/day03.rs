@@ -0,0 +1,16 @@
+#![no_main]
+
+use advent_of_code_2023::day03::Day03;
+use advent_of_code_2023::AoCProblem;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+    let mut day = Day03::default();
+    if day.parse_input(input).is_ok() {
+        let _ = day.part_1();
+        let _ = day.part_2();
+    }
+});
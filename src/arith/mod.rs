@@ -0,0 +1,105 @@
+use std::fmt::Display;
+
+use color_eyre::eyre::{eyre, Result};
+
+/// Integer types that support the checked operations the solvers need.
+pub trait CheckedInt: Copy {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! checked_int {
+    ($($t:ty),*) => {
+        $(impl CheckedInt for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_mul(self, rhs)
+            }
+        })*
+    };
+}
+
+checked_int!(u32, u64, u128, usize, i32, i64, i128);
+
+pub trait OrOverflow<T> {
+    /// Turns a `None` from a checked operation into an overflow error mentioning `what`.
+    fn or_overflow(self, what: impl Display) -> Result<T>;
+}
+
+impl<T> OrOverflow<T> for Option<T> {
+    fn or_overflow(self, what: impl Display) -> Result<T> {
+        self.ok_or_else(|| eyre!("arithmetic overflow while computing {}", what))
+    }
+}
+
+pub trait CheckedIterator<T: CheckedInt>: Iterator<Item = T> + Sized {
+    fn checked_sum(mut self) -> Result<T> {
+        self.try_fold(T::ZERO, T::checked_add).or_overflow("a sum")
+    }
+
+    fn checked_product(mut self) -> Result<T> {
+        self.try_fold(T::ONE, T::checked_mul)
+            .or_overflow("a product")
+    }
+}
+
+impl<T: CheckedInt, I: Iterator<Item = T>> CheckedIterator<T> for I {}
+
+/// Same as [`CheckedIterator`] for fallible items, the first error wins.
+pub trait TryCheckedIterator<T: CheckedInt>: Iterator<Item = Result<T>> + Sized {
+    fn try_checked_sum(mut self) -> Result<T> {
+        self.try_fold(T::ZERO, |acc, n| acc.checked_add(n?).or_overflow("a sum"))
+    }
+
+    fn try_checked_product(mut self) -> Result<T> {
+        self.try_fold(T::ONE, |acc, n| {
+            acc.checked_mul(n?).or_overflow("a product")
+        })
+    }
+}
+
+impl<T: CheckedInt, I: Iterator<Item = Result<T>>> TryCheckedIterator<T> for I {}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn sums_and_products() {
+        assert_eq!([1_u32, 2, 3].into_iter().checked_sum().unwrap(), 6);
+        assert_eq!([2_i64, -3, 4].into_iter().checked_product().unwrap(), -24);
+        assert_eq!(Vec::<u64>::new().into_iter().checked_product().unwrap(), 1);
+
+        let err = [u32::MAX, 1].into_iter().checked_sum().unwrap_err();
+        assert_eq!(err.to_string(), "arithmetic overflow while computing a sum");
+        assert!([u64::MAX, 2].into_iter().checked_product().is_err());
+
+        assert_eq!(
+            [Ok(2_u64), Ok(3)]
+                .into_iter()
+                .try_checked_product()
+                .unwrap(),
+            6
+        );
+        let results = [Ok(1_u64), Err(eyre!("bad item")), Ok(u64::MAX)];
+        assert_eq!(
+            results
+                .into_iter()
+                .try_checked_sum()
+                .unwrap_err()
+                .to_string(),
+            "bad item"
+        );
+    }
+}
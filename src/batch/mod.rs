@@ -0,0 +1,171 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{eyre, Result, WrapErr};
+
+use crate::external::answer_lines;
+use crate::{day, Solution};
+
+/// The inputs of day `number` live in `root/dayNN`.
+pub fn day_directory(root: &Path, number: u32) -> PathBuf {
+    root.join(format!("day{:02}", number))
+}
+
+/// One input of a batch. The expected answers come from a `.answers` file next to the input,
+/// in the same two line format external solvers print.
+#[derive(Debug)]
+pub struct BatchResult {
+    pub name: String,
+    pub expected: (Option<String>, Option<String>),
+    pub solution: Result<Solution>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong,
+    /// There is an answer but nothing to check it against, or no answer at all.
+    Unchecked,
+}
+
+impl BatchResult {
+    pub fn verdict(&self, part: usize) -> Verdict {
+        let Ok(solution) = &self.solution else {
+            return Verdict::Wrong;
+        };
+        let (answer, expected) = match part {
+            1 => (&solution.part_1, &self.expected.0),
+            _ => (&solution.part_2, &self.expected.1),
+        };
+        match (answer, expected) {
+            (Some(a), Some(e)) if a == e => Verdict::Correct,
+            (_, Some(_)) => Verdict::Wrong,
+            (_, None) => Verdict::Unchecked,
+        }
+    }
+}
+
+/// Solves day `number` for every `*.txt` file in `dir`, in file name order.
+pub fn run_directory(number: u32, dir: &Path) -> Result<Vec<BatchResult>> {
+    let mut inputs = fs::read_dir(dir)
+        .wrap_err_with(|| format!("reading {}", dir.display()))?
+        .map(|entry| Ok(entry?.path()))
+        .filter(|path| {
+            path.as_ref()
+                .map_or(true, |p| p.extension().is_some_and(|e| e == "txt"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if inputs.is_empty() {
+        return Err(eyre!("there are no .txt files in {}", dir.display()));
+    }
+    inputs.sort();
+
+    inputs
+        .into_iter()
+        .map(|path| {
+            let input = fs::read_to_string(&path)
+                .wrap_err_with(|| format!("reading {}", path.display()))?;
+            let expected = match fs::read_to_string(path.with_extension("answers")) {
+                Ok(answers) => answer_lines(&answers),
+                Err(_) => (None, None),
+            };
+            let mut day = day(number)?;
+
+            Ok(BatchResult {
+                name: path.file_name().unwrap().to_string_lossy().into(),
+                expected,
+                solution: Solution::measure(&mut *day, |d| d.parse_input(&input)),
+            })
+        })
+        .collect()
+}
+
+/// Prints one row per input and returns whether none of them were wrong or failed.
+pub fn print_matrix(results: &[BatchResult]) -> bool {
+    let width = results
+        .iter()
+        .map(|r| r.name.len())
+        .max()
+        .unwrap_or(0)
+        .max(5);
+    println!(
+        "\t{:<width$}  {:<24}{:<24}time",
+        "input",
+        "part 1",
+        "part 2",
+        width = width
+    );
+
+    let mut ok = true;
+    for result in results {
+        let solution = match &result.solution {
+            Ok(solution) => solution,
+            Err(e) => {
+                ok = false;
+                println!("\t{:<width$}  error: {}", result.name, e, width = width);
+                continue;
+            }
+        };
+
+        let cells = [
+            (1, &solution.part_1, &result.expected.0),
+            (2, &solution.part_2, &result.expected.1),
+        ]
+        .map(|(part, answer, expected)| {
+            let answer = answer.as_deref().unwrap_or("-");
+            match (result.verdict(part), expected) {
+                (Verdict::Correct, _) => format!("{} ok", answer),
+                (Verdict::Wrong, Some(expected)) => {
+                    ok = false;
+                    format!("{} != {}", answer, expected)
+                }
+                _ => answer.to_string(),
+            }
+        });
+        println!(
+            "\t{:<width$}  {:<24}{:<24}{:?}",
+            result.name,
+            cells[0],
+            cells[1],
+            solution.total_time(),
+            width = width
+        );
+    }
+    println!();
+
+    ok
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn directory_of_inputs() {
+        let dir = std::env::temp_dir().join(format!("aoc-batch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let example = "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet\n";
+        fs::write(dir.join("a.txt"), example).unwrap();
+        fs::write(dir.join("a.answers"), "142\n-\n").unwrap();
+        fs::write(dir.join("b.txt"), example).unwrap();
+        fs::write(dir.join("b.answers"), "143\n").unwrap();
+        fs::write(dir.join("c.txt"), "abc\n").unwrap();
+        fs::write(dir.join("notes.md"), "not an input").unwrap();
+
+        let results = run_directory(1, &dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let names = results.iter().map(|r| r.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["a.txt", "b.txt", "c.txt"]);
+        assert_eq!(results[0].verdict(1), Verdict::Correct);
+        assert_eq!(results[0].verdict(2), Verdict::Unchecked);
+        assert_eq!(results[1].verdict(1), Verdict::Wrong);
+        assert_eq!(results[2].verdict(1), Verdict::Wrong);
+        assert!(!print_matrix(&results));
+        assert!(print_matrix(&results[..1]));
+
+        assert!(run_directory(1, Path::new("does/not/exist")).is_err());
+    }
+}
@@ -0,0 +1,141 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use color_eyre::eyre::{Result, WrapErr};
+
+use crate::external::answer_lines;
+use crate::{AoCProblem, Solution};
+
+/// Answers are only cached when this names a directory to keep them in.
+pub const CACHE_VAR: &str = "AOC_CACHE";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CacheMode {
+    #[default]
+    Use,
+    /// Neither read nor write the cache.
+    Off,
+    /// Solve again and overwrite what is cached.
+    Refresh,
+}
+
+/// Answers of earlier runs, one file per day, variant, solver version and input. A day that
+/// changes its answers has to change its [`AoCProblem::solver_version`] to invalidate them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cache {
+    dir: PathBuf,
+    refresh: bool,
+}
+
+impl Cache {
+    pub fn new(dir: impl Into<PathBuf>, refresh: bool) -> Self {
+        Self {
+            dir: dir.into(),
+            refresh,
+        }
+    }
+
+    /// The cache in the directory [`CACHE_VAR`] points to, if it is set and `mode` allows it.
+    pub fn from_env(mode: CacheMode) -> Option<Self> {
+        if mode == CacheMode::Off {
+            return None;
+        }
+        let dir = std::env::var_os(CACHE_VAR)?;
+        Some(Self::new(dir, mode == CacheMode::Refresh))
+    }
+
+    /// The cached answers of `day` on `input`, solving it and caching the answers when there
+    /// are none. Cached solutions only take the time of the lookup.
+    pub fn solve(
+        &self,
+        day: &mut dyn AoCProblem,
+        number: u32,
+        variant: &str,
+        input: &str,
+    ) -> Result<Solution> {
+        let path = self.dir.join(format!(
+            "day{:02}-{}-{}-{:016x}",
+            number,
+            variant,
+            day.solver_version(),
+            input_hash(input)
+        ));
+
+        if !self.refresh {
+            let start = Instant::now();
+            if let Ok(answers) = fs::read_to_string(&path) {
+                let (part_1, part_2) = answer_lines(&answers);
+                return Ok(Solution {
+                    part_1,
+                    part_2,
+                    parse_time: start.elapsed(),
+                    cached: true,
+                    ..Solution::default()
+                });
+            }
+        }
+
+        let solution = Solution::measure(day, |d| d.parse_input(input))?;
+        let show = |a: &Option<String>| a.clone().unwrap_or_else(|| "-".into());
+        fs::create_dir_all(&self.dir)
+            .and_then(|()| {
+                fs::write(
+                    &path,
+                    format!("{}\n{}\n", show(&solution.part_1), show(&solution.part_2)),
+                )
+            })
+            .wrap_err_with(|| format!("writing {}", path.display()))?;
+
+        Ok(solution)
+    }
+}
+
+/// 64 bit FNV-1a, which unlike the standard library's hasher stays the same across builds.
+pub fn input_hash(input: &str) -> u64 {
+    input.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::day;
+
+    use super::*;
+
+    #[test]
+    fn answers_come_back_cached() {
+        let dir = std::env::temp_dir().join(format!("aoc-cache-{}", std::process::id()));
+        let example = "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet\n";
+        let solve = |refresh, input| {
+            Cache::new(&dir, refresh)
+                .solve(&mut *day(1).unwrap(), 1, "default", input)
+                .unwrap()
+        };
+
+        let fresh = solve(false, example);
+        let cached = solve(false, example);
+        let refreshed = solve(true, example);
+        let other = solve(false, "12\n");
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(!fresh.cached);
+        assert!(cached.cached);
+        assert_eq!(
+            (&cached.part_1, &cached.part_2),
+            (&fresh.part_1, &fresh.part_2)
+        );
+        assert!(!refreshed.cached);
+        assert!(!other.cached);
+        assert_eq!(other.part_1.as_deref(), Some("12"));
+    }
+
+    #[test]
+    fn hash_is_stable() {
+        assert_eq!(input_hash(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(input_hash("a"), 0xaf63_dc4c_8601_ec8c);
+    }
+}
@@ -0,0 +1,855 @@
+use std::fmt::Display;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{eyre, Result, WrapErr};
+
+use crate::batch;
+use crate::cache::{Cache, CacheMode, CACHE_VAR};
+use crate::day06::Day06;
+use crate::day07::rules::RuleSet;
+use crate::day07::Day07;
+use crate::external::External;
+use crate::inspect::inspect;
+use crate::reduce::{reduce, Check};
+use crate::rng::Rng;
+use crate::stream::{solve_lines, LineSolver, STREAM_THRESHOLD};
+use crate::{day, days, days_implemented, AoCProblem, Solution};
+
+pub const USAGE: &str = "usage:
+    advent_of_code_2023 [--no-cache|--refresh]          solve every day
+    advent_of_code_2023 solve <day> [--variant V | --stream] [--input FILE|-]
+                              [--no-cache|--refresh]    solve one day, large inputs and stdin
+                                                        are read line by line where possible,
+                                                        unless a variant is asked for
+    advent_of_code_2023 crosscheck <day> [--input FILE | --size N --seed S]
+                                                        compare every variant of a day
+    advent_of_code_2023 inspect <day> [--input FILE | --size N --seed S]
+                                                        describe an input and flag numbers too
+                                                        large for the solver
+    advent_of_code_2023 races [--input FILE | --size N --seed S]
+                                                        how each day 6 race can be won
+    advent_of_code_2023 explain 7 <hand> [<hand>] [--input FILE | --size N --seed S]
+                                                        what a day 7 hand is worth with and
+                                                        without jokers, or which of two wins
+    advent_of_code_2023 generate <day> [--size N] [--seed S]
+                                                        print a generated input
+    advent_of_code_2023 bench <day> [--size N] [--seed S] [--variant V]
+                                                        time a day on a generated input
+    advent_of_code_2023 reduce <day> <input> [--check panic|error|mismatch]
+                                                        shrink an input that makes a day fail
+    advent_of_code_2023 diff-run [<day>...] --command CMD [--input FILE]
+                                                        compare with another solver, `{day}`
+                                                        in CMD becomes the day number
+    advent_of_code_2023 batch [<day>...] [--dir DIR]
+                                                        solve every DIR/dayNN/*.txt (default
+                                                        DIR is inputs) and check the answers
+                                                        in the matching .answers files
+
+With AOC_CACHE set to a directory, answers of solved inputs are kept there and reused until the
+input or the solver changes. --no-cache ignores the cache, --refresh solves again.";
+
+const DEFAULT_SIZE: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    All {
+        cache: CacheMode,
+    },
+    Solve {
+        day: u32,
+        variant: Option<String>,
+        input: Option<PathBuf>,
+        stream: bool,
+        cache: CacheMode,
+    },
+    Crosscheck {
+        day: u32,
+        input: InputSource,
+    },
+    Inspect {
+        day: u32,
+        input: InputSource,
+    },
+    Races {
+        input: InputSource,
+    },
+    Explain {
+        day: u32,
+        hands: Vec<String>,
+        input: InputSource,
+    },
+    Generate {
+        day: u32,
+        size: usize,
+        seed: u64,
+    },
+    Bench {
+        day: u32,
+        size: usize,
+        seed: u64,
+        variant: Option<String>,
+    },
+    Reduce {
+        day: u32,
+        input: PathBuf,
+        check: Option<Check>,
+    },
+    DiffRun {
+        days: Vec<u32>,
+        command: External,
+        input: Option<PathBuf>,
+    },
+    Batch {
+        days: Vec<u32>,
+        dir: PathBuf,
+    },
+}
+
+impl Command {
+    /// Parses the command line, without the program name.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut args = Args(args.into_iter().collect());
+        let Some(name) = args.take_positional() else {
+            let command = Self::All {
+                cache: args.cache_mode()?,
+            };
+            args.finish()?;
+            return Ok(command);
+        };
+
+        let command = match name.as_str() {
+            "solve" => {
+                let variant = args.option("--variant")?;
+                let stream = args.switch("--stream");
+                if stream && variant.is_some() {
+                    return Err(eyre!("--stream does not go with --variant"));
+                }
+                Self::Solve {
+                    variant,
+                    input: args.option("--input")?,
+                    stream,
+                    cache: args.cache_mode()?,
+                    day: args.day()?,
+                }
+            }
+            "crosscheck" => Self::Crosscheck {
+                input: args.input_source()?,
+                day: args.day()?,
+            },
+            "inspect" => Self::Inspect {
+                input: args.input_source()?,
+                day: args.day()?,
+            },
+            "races" => Self::Races {
+                input: args.input_source()?,
+            },
+            "explain" => {
+                let input = args.input_source()?;
+                let day = args.day()?;
+                let hands = std::iter::from_fn(|| args.take_positional()).collect::<Vec<_>>();
+                if !(1..=2).contains(&hands.len()) {
+                    return Err(eyre!(
+                        "explain takes one hand, or two to compare\n{}",
+                        USAGE
+                    ));
+                }
+                Self::Explain { day, hands, input }
+            }
+            "generate" => Self::Generate {
+                size: args.option("--size")?.unwrap_or(DEFAULT_SIZE),
+                seed: args.option("--seed")?.unwrap_or(0),
+                day: args.day()?,
+            },
+            "bench" => Self::Bench {
+                size: args.option("--size")?.unwrap_or(DEFAULT_SIZE),
+                seed: args.option("--seed")?.unwrap_or(0),
+                variant: args.option("--variant")?,
+                day: args.day()?,
+            },
+            "diff-run" => {
+                let command = args
+                    .option::<String>("--command")?
+                    .ok_or_else(|| eyre!("diff-run needs a --command"))?;
+                Self::DiffRun {
+                    command: External::parse(&command)?,
+                    input: args.option("--input")?,
+                    days: args.days()?,
+                }
+            }
+            "batch" => Self::Batch {
+                dir: args.option("--dir")?.unwrap_or_else(|| "inputs".into()),
+                days: args.days()?,
+            },
+            "reduce" => {
+                let check = args.option("--check")?;
+                let day = args.day()?;
+                let input = args
+                    .take_positional()
+                    .ok_or_else(|| eyre!("missing input file\n{}", USAGE))?;
+                Self::Reduce {
+                    day,
+                    input: input.into(),
+                    check,
+                }
+            }
+            _ => return Err(eyre!("unknown command {:?}\n{}", name, USAGE)),
+        };
+        args.finish()?;
+
+        Ok(command)
+    }
+}
+
+/// Where a command gets its input from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    Puzzle,
+    File(PathBuf),
+    Generated { size: usize, seed: u64 },
+}
+
+impl InputSource {
+    fn read(&self, day: &dyn AoCProblem) -> Result<String> {
+        Ok(match self {
+            Self::Puzzle => day.puzzle_input().into(),
+            Self::File(path) if path == Path::new("-") => io::read_to_string(io::stdin())?,
+            Self::File(path) => {
+                fs::read_to_string(path).wrap_err_with(|| format!("reading {}", path.display()))?
+            }
+            Self::Generated { size, seed } => day.generate_input(&mut Rng::new(*seed), *size),
+        })
+    }
+}
+
+/// What is left of the command line. Options are taken out first, whatever remains are the
+/// positional arguments.
+struct Args(Vec<String>);
+
+impl Args {
+    fn option<T: FromStr>(&mut self, name: &str) -> Result<Option<T>>
+    where
+        T::Err: Display,
+    {
+        let Some(i) = self.0.iter().position(|a| a == name) else {
+            return Ok(None);
+        };
+        if i + 1 >= self.0.len() {
+            return Err(eyre!("{} needs a value", name));
+        }
+        let value = self.0.remove(i + 1);
+        self.0.remove(i);
+
+        value
+            .parse()
+            .map(Some)
+            .map_err(|e| eyre!("invalid value {:?} for {}: {}", value, name, e))
+    }
+
+    fn switch(&mut self, name: &str) -> bool {
+        let len = self.0.len();
+        self.0.retain(|a| a != name);
+        self.0.len() != len
+    }
+
+    /// `--input FILE`, or a generated input as soon as `--size` or `--seed` is given.
+    fn input_source(&mut self) -> Result<InputSource> {
+        let file = self.option("--input")?;
+        let size = self.option("--size")?;
+        let seed = self.option("--seed")?;
+        Ok(match (file, size, seed) {
+            (Some(_), Some(_), _) | (Some(_), _, Some(_)) => {
+                return Err(eyre!("--input does not go with --size or --seed"))
+            }
+            (Some(file), None, None) => InputSource::File(file),
+            (None, None, None) => InputSource::Puzzle,
+            (None, size, seed) => InputSource::Generated {
+                size: size.unwrap_or(DEFAULT_SIZE),
+                seed: seed.unwrap_or(0),
+            },
+        })
+    }
+
+    fn cache_mode(&mut self) -> Result<CacheMode> {
+        match (self.switch("--no-cache"), self.switch("--refresh")) {
+            (true, true) => Err(eyre!("--no-cache does not go with --refresh")),
+            (true, false) => Ok(CacheMode::Off),
+            (false, true) => Ok(CacheMode::Refresh),
+            (false, false) => Ok(CacheMode::Use),
+        }
+    }
+
+    fn take_positional(&mut self) -> Option<String> {
+        let i = self.0.iter().position(|a| !a.starts_with("--"))?;
+        Some(self.0.remove(i))
+    }
+
+    fn day(&mut self) -> Result<u32> {
+        let day = self
+            .take_positional()
+            .ok_or_else(|| eyre!("missing day\n{}", USAGE))?;
+        day.parse()
+            .wrap_err_with(|| format!("invalid day {:?}", day))
+    }
+
+    /// All remaining positional arguments as days, every day if there are none.
+    fn days(&mut self) -> Result<Vec<u32>> {
+        let mut days = Vec::new();
+        while let Some(day) = self.take_positional() {
+            days.push(
+                day.parse()
+                    .wrap_err_with(|| format!("invalid day {:?}", day))?,
+            );
+        }
+        if days.is_empty() {
+            days.extend(1..=days_implemented());
+        }
+        Ok(days)
+    }
+
+    fn finish(self) -> Result<()> {
+        match self.0.first() {
+            Some(arg) => Err(eyre!("unexpected argument {:?}\n{}", arg, USAGE)),
+            None => Ok(()),
+        }
+    }
+}
+
+pub fn run(command: Command) -> Result<()> {
+    match command {
+        Command::All { cache } => {
+            let cache = Cache::from_env(cache);
+            let days = days();
+            let n_days = days.len();
+            let mut total = Duration::from_secs(0);
+            for (n, mut day) in (1..).zip(days) {
+                total += match &cache {
+                    Some(cache) => {
+                        let (variant, input) = (day.variants()[0], day.puzzle_input());
+                        print_cached(cache, &mut *day, n, variant, input)?
+                    }
+                    None => day.print_solution()?,
+                };
+            }
+            println!("Total time for {} days: {:?}", n_days, total);
+        }
+        Command::Solve {
+            day: n,
+            variant,
+            input,
+            stream,
+            cache,
+        } => {
+            let mut day = day(n)?;
+            if let Some(variant) = &variant {
+                day.select_variant(variant)?;
+            }
+            let source = InputSource::from(input);
+            // Line solvers have no variants, a large input is read whole when one is asked for.
+            if stream || (variant.is_none() && source.is_large()?) {
+                if let Some(solver) = day.line_solver() {
+                    return print_streamed(&*day, solver, source);
+                }
+                if stream {
+                    return Err(eyre!("{} cannot be streamed", day.get_day_name()));
+                }
+            }
+            let input = source.read(&*day)?;
+            match Cache::from_env(cache) {
+                Some(cache) => {
+                    let variant = variant.as_deref().unwrap_or(day.variants()[0]);
+                    print_cached(&cache, &mut *day, n, variant, &input)?;
+                }
+                None => {
+                    day.print_solution_for(&input)?;
+                }
+            }
+        }
+        Command::Crosscheck { day: n, input } => {
+            let input = input.read(&*day(n)?)?;
+            let results = crosscheck(n, &input)?;
+            print_crosscheck(n, &results)?;
+        }
+        Command::Inspect { day: n, input } => {
+            let mut day = day(n)?;
+            let input = input.read(&*day)?;
+            let stats = inspect(&mut *day, &input)?;
+            println!("{}:", day.get_day_name());
+            stats.print();
+        }
+        Command::Races { input } => {
+            let mut day = Day06::default();
+            let input = input.read(&day)?;
+            day.parse_input(&input)?;
+            for (i, race) in day.races().iter().enumerate() {
+                print!("Race {}: {}", i + 1, race.analyse());
+            }
+            print!("Concatenated race: {}", day.concatenated_race().analyse());
+        }
+        Command::Explain {
+            day: n,
+            hands,
+            input,
+        } => {
+            if n != 7 {
+                return Err(eyre!("only day 7 has hands to explain, not day {}", n));
+            }
+            let mut day = Day07::default();
+            let input = input.read(&day)?;
+            day.parse_input(&input)?;
+            for (part, rules) in [(1, RuleSet::standard()), (2, RuleSet::jokers())] {
+                println!("Part {}:", part);
+                for hand in &hands {
+                    print!("{}", day.explain(hand, &rules)?);
+                }
+                if let [a, b] = &hands[..] {
+                    print!("{}", day.compare(a, b, &rules)?);
+                }
+            }
+        }
+        Command::Generate { day: n, size, seed } => {
+            let input = day(n)?.generate_input(&mut Rng::new(seed), size);
+            io::stdout().lock().write_all(input.as_bytes())?;
+        }
+        Command::Bench {
+            day: n,
+            size,
+            seed,
+            variant,
+        } => {
+            let mut day = day(n)?;
+            if let Some(variant) = variant {
+                day.select_variant(&variant)?;
+            }
+            let input = day.generate_input(&mut Rng::new(seed), size);
+            println!(
+                "Generated {} bytes ({} lines) with size {} and seed {}\n",
+                input.len(),
+                input.lines().count(),
+                size,
+                seed
+            );
+            day.print_solution_for(&input)?;
+        }
+        Command::Reduce {
+            day: n,
+            input,
+            check,
+        } => {
+            let input = fs::read_to_string(&input)
+                .wrap_err_with(|| format!("reading {}", input.display()))?;
+            let (reduced, failure) = reduce(n, &input, check)?;
+            eprintln!(
+                "{}\nreduced {} lines to {}:\n",
+                failure,
+                input.lines().count(),
+                reduced.lines().count()
+            );
+            io::stdout().lock().write_all(reduced.as_bytes())?;
+        }
+        Command::DiffRun {
+            days,
+            command,
+            input,
+        } => {
+            let source = InputSource::from(input);
+            let mut agree = true;
+            for n in days {
+                let mut day = day(n)?;
+                let input = source.read(&*day)?;
+                println!("{}:", day.get_day_name());
+                agree &= diff_run(&mut *day, n, &command, &input);
+            }
+            if !agree {
+                return Err(eyre!("the answers differ"));
+            }
+        }
+        Command::Batch { days, dir } => {
+            let mut ok = true;
+            for n in days {
+                println!("{}:", day(n)?.get_day_name());
+                let results = batch::run_directory(n, &batch::day_directory(&dir, n))?;
+                ok &= batch::print_matrix(&results);
+            }
+            if !ok {
+                return Err(eyre!("some inputs failed or gave the wrong answer"));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Like [`AoCProblem::print_solution_for`], with the answers taken from `cache` when they are
+/// there.
+fn print_cached(
+    cache: &Cache,
+    day: &mut dyn AoCProblem,
+    number: u32,
+    variant: &str,
+    input: &str,
+) -> Result<Duration> {
+    println!("{}:", day.get_day_name());
+    let solution = cache
+        .solve(day, number, variant, input)
+        .wrap_err_with(|| format!("with the cache in ${}", CACHE_VAR))?;
+    solution.print();
+
+    Ok(solution.total_time())
+}
+
+/// Prints how `command` compares with `day` on `input`, `false` if the answers differ or
+/// either side failed. A part only one side answers is not compared.
+fn diff_run(day: &mut dyn AoCProblem, number: u32, command: &External, input: &str) -> bool {
+    let ours = Solution::measure(day, |d| d.parse_input(input));
+    let theirs = command.run(number, input);
+    let (ours, theirs) = match (ours, theirs) {
+        (Ok(ours), Ok(theirs)) => (ours, theirs),
+        (ours, theirs) => {
+            if let Err(e) = ours {
+                println!("\tour solver failed: {}", e);
+            }
+            if let Err(e) = theirs {
+                println!("\ttheir solver failed: {}", e);
+            }
+            println!();
+            return false;
+        }
+    };
+
+    let mut agree = true;
+    for (part, ours, theirs) in [
+        (1, &ours.part_1, &theirs.part_1),
+        (2, &ours.part_2, &theirs.part_2),
+    ] {
+        let verdict = match (ours, theirs) {
+            (Some(a), Some(b)) if a == b => "same",
+            (Some(_), Some(_)) => {
+                agree = false;
+                "DIFFERENT"
+            }
+            _ => "not compared",
+        };
+        let show = |a: &Option<String>| a.clone().unwrap_or_else(|| "-".into());
+        println!(
+            "\tPart {}: ours {}, theirs {}, {}",
+            part,
+            show(ours),
+            show(theirs),
+            verdict
+        );
+    }
+    println!(
+        "\tTook: ours {:?}, theirs {:?}\n",
+        ours.total_time(),
+        theirs.time
+    );
+
+    agree
+}
+
+impl InputSource {
+    /// Whether the input is worth streaming. Stdin counts as large since there is no telling.
+    fn is_large(&self) -> Result<bool> {
+        Ok(match self {
+            Self::File(path) if path == Path::new("-") => true,
+            Self::File(path) => fs::metadata(path)?.len() >= STREAM_THRESHOLD,
+            Self::Puzzle | Self::Generated { .. } => false,
+        })
+    }
+
+    fn reader(&self, day: &dyn AoCProblem) -> Result<Box<dyn BufRead>> {
+        Ok(match self {
+            Self::File(path) if path == Path::new("-") => Box::new(io::stdin().lock()),
+            Self::File(path) => Box::new(BufReader::new(
+                File::open(path).wrap_err_with(|| format!("opening {}", path.display()))?,
+            )),
+            _ => Box::new(io::Cursor::new(self.read(day)?)),
+        })
+    }
+}
+
+fn print_streamed(
+    day: &dyn AoCProblem,
+    solver: Box<dyn LineSolver>,
+    source: InputSource,
+) -> Result<()> {
+    println!("{}:", day.get_day_name());
+    let start = Instant::now();
+    let (part_1, part_2) = solve_lines(solver, source.reader(day)?)?;
+    let elapsed = start.elapsed();
+
+    println!("\tStreamed the input line by line\n");
+    for (part, answer) in [(1, part_1), (2, part_2)] {
+        if let Some(answer) = answer {
+            println!("\tPart {}: {}", part, answer);
+        }
+    }
+    println!("\n\tTotal time: {:?}\n", elapsed);
+
+    Ok(())
+}
+
+impl From<Option<PathBuf>> for InputSource {
+    fn from(file: Option<PathBuf>) -> Self {
+        file.map_or(Self::Puzzle, Self::File)
+    }
+}
+
+/// Runs every variant of day `number` on `input`. A variant that fails is reported rather
+/// than stopping the others.
+pub fn crosscheck(number: u32, input: &str) -> Result<Vec<(&'static str, Result<Solution>)>> {
+    day(number)?
+        .variants()
+        .iter()
+        .map(|&name| {
+            let mut day = day(number)?;
+            day.select_variant(name)?;
+            Ok((name, Solution::measure(&mut *day, |d| d.parse_input(input))))
+        })
+        .collect()
+}
+
+fn print_crosscheck(number: u32, results: &[(&str, Result<Solution>)]) -> Result<()> {
+    let fastest = results
+        .iter()
+        .filter_map(|(_, r)| r.as_ref().ok())
+        .map(Solution::solve_time)
+        .min()
+        .unwrap_or_default();
+    let answer = |r: &Result<Solution>, part: fn(&Solution) -> &Option<String>| match r {
+        Ok(solution) => part(solution).clone().unwrap_or_else(|| "-".into()),
+        Err(e) => format!("error: {}", e),
+    };
+
+    println!("{}:", day(number)?.get_day_name());
+    for (name, result) in results {
+        print!(
+            "\t{:<16}part 1: {:<20}part 2: {:<20}",
+            name,
+            answer(result, |s| &s.part_1),
+            answer(result, |s| &s.part_2)
+        );
+        match result {
+            Ok(s) if !fastest.is_zero() => println!(
+                "{:?} ({:.2}x)",
+                s.solve_time(),
+                s.solve_time().as_secs_f64() / fastest.as_secs_f64()
+            ),
+            Ok(s) => println!("{:?}", s.solve_time()),
+            Err(_) => println!(),
+        }
+    }
+
+    let mut agree = true;
+    for (part, get) in [
+        (1, (|s| &s.part_1) as fn(&Solution) -> &Option<String>),
+        (2, |s| &s.part_2),
+    ] {
+        let answers = results
+            .iter()
+            .map(|(name, r)| (name, answer(r, get)))
+            .collect::<Vec<_>>();
+        if answers.iter().any(|(_, a)| *a != answers[0].1) {
+            agree = false;
+            println!("\tPart {} disagrees:", part);
+            for (name, a) in answers {
+                println!("\t\t{}: {}", name, a);
+            }
+        }
+    }
+
+    if agree {
+        Ok(())
+    } else {
+        Err(eyre!("the variants of day {} disagree", number))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn parse(args: &str) -> Result<Command> {
+        Command::parse(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn commands() {
+        assert_eq!(
+            parse("").unwrap(),
+            Command::All {
+                cache: CacheMode::Use
+            }
+        );
+        assert_eq!(
+            parse("--refresh").unwrap(),
+            Command::All {
+                cache: CacheMode::Refresh
+            }
+        );
+        assert!(parse("--no-cache --refresh").is_err());
+        assert_eq!(
+            parse("generate 5 --seed 3").unwrap(),
+            Command::Generate {
+                day: 5,
+                size: DEFAULT_SIZE,
+                seed: 3
+            }
+        );
+        assert_eq!(
+            parse("bench --size 10 7").unwrap(),
+            Command::Bench {
+                day: 7,
+                size: 10,
+                seed: 0,
+                variant: None
+            }
+        );
+        assert_eq!(
+            parse("solve 4 --variant queue").unwrap(),
+            Command::Solve {
+                day: 4,
+                variant: Some("queue".into()),
+                input: None,
+                stream: false,
+                cache: CacheMode::Use
+            }
+        );
+        assert!(parse("solve 4 --stream --variant queue").is_err());
+        assert_eq!(
+            parse("solve --stream 7 --input - --no-cache").unwrap(),
+            Command::Solve {
+                day: 7,
+                variant: None,
+                input: Some("-".into()),
+                stream: true,
+                cache: CacheMode::Off
+            }
+        );
+        assert_eq!(
+            parse("crosscheck 6 --seed 2").unwrap(),
+            Command::Crosscheck {
+                day: 6,
+                input: InputSource::Generated {
+                    size: DEFAULT_SIZE,
+                    seed: 2
+                }
+            }
+        );
+        assert!(parse("crosscheck 6 --seed 2 --input x").is_err());
+        assert_eq!(
+            parse("races").unwrap(),
+            Command::Races {
+                input: InputSource::Puzzle
+            }
+        );
+        assert_eq!(
+            parse("explain 7 KTJJT").unwrap(),
+            Command::Explain {
+                day: 7,
+                hands: vec!["KTJJT".into()],
+                input: InputSource::Puzzle
+            }
+        );
+        assert_eq!(
+            parse("explain 7 KTJJT QQQJA --size 10").unwrap(),
+            Command::Explain {
+                day: 7,
+                hands: vec!["KTJJT".into(), "QQQJA".into()],
+                input: InputSource::Generated { size: 10, seed: 0 }
+            }
+        );
+        assert!(parse("explain 7").is_err());
+        assert!(parse("explain 7 KTJJT QQQJA 32T3K").is_err());
+        assert_eq!(
+            parse("inspect 5 --input big.txt").unwrap(),
+            Command::Inspect {
+                day: 5,
+                input: InputSource::File("big.txt".into())
+            }
+        );
+        assert_eq!(
+            parse("reduce 8 input.txt --check mismatch").unwrap(),
+            Command::Reduce {
+                day: 8,
+                input: "input.txt".into(),
+                check: Some(Check::Mismatch)
+            }
+        );
+        assert_eq!(
+            parse("diff-run 5 6 --command ./solve").unwrap(),
+            Command::DiffRun {
+                days: vec![5, 6],
+                command: External::new("./solve", Vec::<String>::new()),
+                input: None
+            }
+        );
+        assert!(matches!(
+            parse("diff-run --command ./solve").unwrap(),
+            Command::DiffRun { days, .. } if days.len() == 9
+        ));
+        assert!(parse("diff-run 5").is_err());
+        assert_eq!(
+            parse("batch 8").unwrap(),
+            Command::Batch {
+                days: vec![8],
+                dir: "inputs".into()
+            }
+        );
+        assert!(parse("reduce 8").is_err());
+        assert!(parse("reduce 8 input.txt --check hang").is_err());
+        assert!(parse("generate").is_err());
+        assert!(parse("generate x").is_err());
+        assert!(parse("generate 1 --size").is_err());
+        assert!(parse("generate 1 2").is_err());
+        assert!(parse("frobnicate 1").is_err());
+    }
+
+    #[test]
+    fn generated_inputs_parse() {
+        for (n, mut day) in (1..).zip(days()) {
+            for seed in 0..3 {
+                let input = day.generate_input(&mut Rng::new(seed), 50);
+                day.parse_input(&input)
+                    .wrap_err_with(|| format!("day {} seed {}", n, seed))
+                    .unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn variants_agree() {
+        // Day 6 has a brute force variant, which has to try every hold of the concatenated race.
+        for (n, size) in [(4, 20), (5, 20), (6, 5)] {
+            let input = day(n)
+                .unwrap()
+                .generate_input(&mut Rng::new(n as u64), size);
+            let results = crosscheck(n, &input).unwrap();
+            assert_eq!(results.len(), 2);
+            let solutions = results
+                .into_iter()
+                .map(|(_, r)| r.unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(solutions[0].part_1, solutions[1].part_1);
+            assert_eq!(solutions[0].part_2, solutions[1].part_2);
+        }
+    }
+
+    #[test]
+    fn diff_run_against_a_script() {
+        let mut day = day(1).unwrap();
+        let input = "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet\n";
+        let agreeing = External::new("sh", ["-c", "cat >/dev/null; echo 142; echo -"]);
+        assert!(diff_run(&mut *day, 1, &agreeing, input));
+        let differing = External::new("sh", ["-c", "echo 142; echo 0"]);
+        assert!(!diff_run(&mut *day, 1, &differing, input));
+        let failing = External::new("sh", ["-c", "exit 1"]);
+        assert!(!diff_run(&mut *day, 1, &failing, input));
+    }
+}
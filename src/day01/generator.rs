@@ -0,0 +1,37 @@
+use crate::rng::Rng;
+
+const WORDS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+/// `size` calibration lines. Every line has at least one digit so part 1 is defined, and
+/// spelled out (often overlapping) numbers are mixed in for part 2.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..size {
+        let mut line = String::new();
+        let pieces = rng.range_inclusive(0..=8);
+        let digit_at = rng.range_inclusive(0..=pieces);
+        for i in 0..=pieces {
+            if i == digit_at {
+                line.push(char::from(b'1' + rng.below(9) as u8));
+            }
+            match rng.below(4) {
+                0 => {
+                    let word = *rng.choose(&WORDS);
+                    line.push_str(word);
+                }
+                // Overlaps such as "twone" and "eightwo" are the classic trap.
+                1 => {
+                    let word = *rng.choose(&["twone", "eightwo", "oneight", "sevenine"]);
+                    line.push_str(word);
+                }
+                2 => line.push(char::from(b'1' + rng.below(9) as u8)),
+                _ => line.push(char::from(b'a' + rng.below(26) as u8)),
+            }
+        }
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
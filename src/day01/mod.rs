@@ -1,7 +1,15 @@
-use itertools::Itertools;
+use color_eyre::eyre::{eyre, Result};
 
+use crate::arith::{OrOverflow, TryCheckedIterator};
+use crate::inspect::Stats;
+use crate::parallel::prelude::*;
+use crate::rng::Rng;
+use crate::stream::LineSolver;
 use crate::AoCProblem;
 
+pub mod generator;
+pub mod oracle;
+
 #[derive(Debug, Default)]
 pub struct Day01 {
     input: Vec<String>,
@@ -9,95 +17,126 @@ pub struct Day01 {
 
 static INPUT: &str = include_str!("../../inputs/day01.txt");
 
+const WORDS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+#[derive(Default)]
+struct Streaming {
+    part_1: u64,
+    part_2: u64,
+}
+
+impl LineSolver for Streaming {
+    fn feed(&mut self, line: &str) -> Result<()> {
+        let no_digits = || eyre!("the line has no digits");
+        let value = calibration_value(line).ok_or_else(no_digits)?;
+        self.part_1 = self.part_1.checked_add(value).or_overflow("a sum")?;
+        let value = spelled_calibration_value(line).ok_or_else(no_digits)?;
+        self.part_2 = self.part_2.checked_add(value).or_overflow("a sum")?;
+        Ok(())
+    }
+
+    fn finish(self: Box<Self>) -> Result<(Option<String>, Option<String>)> {
+        Ok((Some(self.part_1.to_string()), Some(self.part_2.to_string())))
+    }
+}
+
+/// First and last digit of `line`, `None` if it has none.
+fn calibration_value(line: &str) -> Option<u64> {
+    let mut digits = line.chars().filter_map(|c| c.to_digit(10));
+    let first = digits.next()?;
+    let last = digits.next_back().unwrap_or(first);
+    Some(10 * first as u64 + last as u64)
+}
+
+/// Like [`calibration_value`], but digits may also be spelled out. Spelled digits can overlap
+/// (`"twone"`), so the last one is the one that starts last rather than the last whole word.
+fn spelled_calibration_value(line: &str) -> Option<u64> {
+    let digit_at = |i: usize| {
+        let rest = &line[i..];
+        rest.chars().next()?.to_digit(10).or_else(|| {
+            (1..)
+                .zip(WORDS)
+                .find_map(|(n, w)| rest.starts_with(w).then_some(n))
+        })
+    };
+
+    let starts = line.char_indices().map(|(i, _)| i);
+    let first = starts.clone().find_map(digit_at)?;
+    let last = starts.rev().find_map(digit_at)?;
+    Some(10 * first as u64 + last as u64)
+}
+
 impl AoCProblem for Day01 {
-    fn parse_input(&mut self, input: &str) {
+    fn parse_input(&mut self, input: &str) -> Result<()> {
         self.input = input.lines().map(|l| l.to_string()).collect();
+        Ok(())
     }
 
-    fn parse_input_default(&mut self) {
-        self.parse_input(INPUT)
+    fn puzzle_input(&self) -> &'static str {
+        INPUT
     }
 
-    fn part_1(&self) -> Option<String> {
-        Some(
-            self.input
-                .iter()
-                .map(|l| {
-                    l.chars()
-                        .filter(|c| c.is_ascii_digit())
-                        .map(|c| c.to_digit(10).unwrap())
-                        .collect_vec()
-                })
-                .map(|l| 10 * l.first().unwrap() + l.last().unwrap())
-                .sum::<u32>()
-                .to_string(),
-        )
+    fn part_1(&self) -> Result<Option<String>> {
+        let sum = self
+            .input
+            .maybe_par_iter()
+            .enumerate()
+            .map(|(i, l)| calibration_value(l).ok_or_else(|| eyre!("line {} has no digits", i + 1)))
+            .sequential()
+            .try_checked_sum()?;
+
+        Ok(Some(sum.to_string()))
     }
 
-    fn part_2(&self) -> Option<String> {
-        let needle = [
-            "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
-        ]
-        .map(|s| s.chars().collect_vec());
-        let eldeen = [
-            "eno", "owt", "eerht", "ruof", "evif", "xis", "neves", "thgie", "enin",
-        ]
-        .map(|s| s.chars().collect_vec());
-
-        let res = self
+    fn part_2(&self) -> Result<Option<String>> {
+        let sum = self
             .input
-            .iter()
-            .map(|l| {
-                let mut haystack = l.chars().collect_vec();
-
-                let mut first = 0;
-                'outer: for i in 0..l.len() {
-                    if haystack[i].is_ascii_digit() {
-                        first = haystack[i].to_digit(10).unwrap();
-                        break;
-                    }
-
-                    for j in 0..needle.len() {
-                        // check if the needle is in the haystack
-                        if i + needle[j].len() <= l.len()
-                            && haystack[i..i + needle[j].len()] == needle[j]
-                        {
-                            first = (j + 1) as u32;
-                            break 'outer;
-                        }
-                    }
-                }
-
-                haystack.reverse();
-                let mut last = 0;
-                'outer: for i in 0..l.len() {
-                    if haystack[i].is_ascii_digit() {
-                        last = haystack[i].to_digit(10).unwrap();
-                        break;
-                    }
-
-                    for j in 0..eldeen.len() {
-                        // check if the needle is in the haystack
-                        if i + eldeen[j].len() <= l.len()
-                            && haystack[i..i + eldeen[j].len()] == eldeen[j]
-                        {
-                            last = (j + 1) as u32;
-                            break 'outer;
-                        }
-                    }
-                }
-
-                first * 10 + last
+            .maybe_par_iter()
+            .enumerate()
+            .map(|(i, l)| {
+                spelled_calibration_value(l).ok_or_else(|| eyre!("line {} has no digits", i + 1))
             })
-            .sum::<u32>()
-            .to_string();
+            .sequential()
+            .try_checked_sum()?;
 
-        Some(res)
+        Ok(Some(sum.to_string()))
     }
 
     fn get_day_name(&self) -> String {
         "Day 01: Trebuchet?!".into()
     }
+
+    fn solver_version(&self) -> &'static str {
+        "1"
+    }
+
+    fn inspect(&self, stats: &mut Stats) {
+        stats.range("line length", self.input.iter().map(|l| l.len()));
+        let without_digits = self
+            .input
+            .iter()
+            .filter(|l| calibration_value(l).is_none())
+            .count();
+        stats.fact("lines without digits", without_digits);
+        stats.fits::<u64, _>("largest possible sum", 99 * self.input.len() as u128);
+    }
+
+    fn generate_input(&self, rng: &mut Rng, size: usize) -> String {
+        generator::generate(rng, size)
+    }
+
+    fn line_solver(&self) -> Option<Box<dyn LineSolver>> {
+        Some(Box::<Streaming>::default())
+    }
+
+    fn reference_answers(&self, input: &str) -> (Option<String>, Option<String>) {
+        (
+            Some(oracle::part_1(input).to_string()),
+            Some(oracle::part_2(input).to_string()),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -109,16 +148,28 @@ mod tests {
     #[test]
     fn test_part_1() {
         let mut day = super::Day01::default();
-        day.parse_input("1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet");
+        day.parse_input("1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet")
+            .unwrap();
 
-        assert_eq!(day.part_1(), Some("142".into()));
+        assert_eq!(day.part_1().unwrap(), Some("142".into()));
     }
 
     #[test]
     fn test_part_2() {
         let mut day = super::Day01::default();
-        day.parse_input("two1nine\neightwothree\nabcone2threexyz\nxtwone3four\n4nineeightseven2\nzoneight234\n7pqrstsixteen");
+        day.parse_input("two1nine\neightwothree\nabcone2threexyz\nxtwone3four\n4nineeightseven2\nzoneight234\n7pqrstsixteen").unwrap();
+
+        assert_eq!(day.part_2().unwrap(), Some("281".into()));
+    }
+
+    #[test]
+    fn lines_without_digits() {
+        let mut day = super::Day01::default();
+        day.parse_input("1abc2\nabc").unwrap();
+        assert!(day.part_1().is_err());
+        assert!(day.part_2().is_err());
 
-        assert_eq!(day.part_2(), Some("281".into()));
+        day.parse_input("é1two").unwrap();
+        assert_eq!(day.part_2().unwrap(), Some("12".into()));
     }
 }
@@ -0,0 +1,40 @@
+//! Reference answers that look for a digit, written or spelled out, at every position.
+
+pub fn part_1(input: &str) -> u64 {
+    input
+        .lines()
+        .map(|l| {
+            let digits = l.chars().filter(char::is_ascii_digit).collect::<Vec<_>>();
+            format!("{}{}", digits[0], digits[digits.len() - 1])
+                .parse::<u64>()
+                .unwrap()
+        })
+        .sum()
+}
+
+pub fn part_2(input: &str) -> u64 {
+    input
+        .lines()
+        .map(|l| {
+            // Every position where a digit starts, written or spelled out.
+            let mut found = Vec::new();
+            for i in 0..l.len() {
+                let rest = &l[i..];
+                if let Some(d) = rest.chars().next().and_then(|c| c.to_digit(10)) {
+                    found.push(d as u64);
+                }
+                for (value, word) in [
+                    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+                ]
+                .iter()
+                .enumerate()
+                {
+                    if rest.starts_with(word) {
+                        found.push(value as u64 + 1);
+                    }
+                }
+            }
+            found[0] * 10 + found[found.len() - 1]
+        })
+        .sum()
+}
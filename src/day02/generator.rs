@@ -0,0 +1,23 @@
+use crate::rng::Rng;
+
+/// `size` games of one to five draws each, counts hover around the part 1 limits so that
+/// both possible and impossible games show up.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for game in 1..=size {
+        let draws = (0..rng.range_inclusive(1..=5))
+            .map(|_| {
+                let mut colours = ["red", "green", "blue"];
+                rng.shuffle(&mut colours);
+                let n = rng.range_inclusive(1..=3) as usize;
+                colours[..n]
+                    .iter()
+                    .map(|c| format!("{} {}", rng.range_inclusive(1..=16), c))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .collect::<Vec<_>>();
+        out.push_str(&format!("Game {}: {}\n", game, draws.join("; ")));
+    }
+    out
+}
@@ -1,9 +1,24 @@
 use std::str::FromStr;
 
-use itertools::Itertools;
-
+use color_eyre::eyre::{eyre, Result};
+use color_eyre::Report;
+use nom::bytes::complete::tag;
+use nom::character::complete::{alpha1, char, space1};
+use nom::combinator::map_res;
+use nom::multi::separated_list1;
+use nom::sequence::{preceded, tuple};
+use nom::IResult;
+
+use crate::arith::{CheckedIterator, OrOverflow, TryCheckedIterator};
+use crate::inspect::Stats;
+use crate::parallel::prelude::*;
+use crate::parsing::{key_value, parse_all, records, unsigned};
+use crate::rng::Rng;
 use crate::AoCProblem;
 
+pub mod generator;
+pub mod oracle;
+
 #[derive(Default)]
 pub struct Day02 {
     data: Vec<Vec<CubeSet>>,
@@ -12,47 +27,81 @@ pub struct Day02 {
 static INPUT: &str = include_str!("../../inputs/day02.txt");
 
 impl AoCProblem for Day02 {
-    fn parse_input(&mut self, input: &str) {
-        self.data = input
-            .lines()
-            .map(|l| {
-                l[l.find(": ").unwrap() + 2..]
-                    .split(';')
-                    .map(|b| b.parse().unwrap())
-                    .collect_vec()
-            })
-            .collect_vec()
+    fn parse_input(&mut self, input: &str) -> Result<()> {
+        self.data = maybe_par_lines(input)
+            .map(|l| parse_all(l, parse_game))
+            .sequential()
+            .collect::<Result<_>>()?;
+        Ok(())
     }
 
-    fn parse_input_default(&mut self) {
-        self.parse_input(INPUT)
+    fn puzzle_input(&self) -> &'static str {
+        INPUT
     }
 
-    fn part_1(&self) -> Option<String> {
-        Some(
-            self.data
-                .iter()
-                .enumerate()
-                .filter(|(_, v)| v.iter().all(|c| c.is_valid()))
-                .map(|(i, _)| i + 1)
-                .sum::<usize>()
-                .to_string(),
-        )
+    fn part_1(&self) -> Result<Option<String>> {
+        let sum = self
+            .data
+            .maybe_par_iter()
+            .enumerate()
+            .filter(|(_, v)| v.iter().all(|c| c.is_valid()))
+            .map(|(i, _)| i as u64 + 1)
+            .sequential()
+            .checked_sum()?;
+
+        Ok(Some(sum.to_string()))
     }
 
-    fn part_2(&self) -> Option<String> {
-        Some(
-            self.data
-                .iter()
-                .map(|d| CubeSet::power(d))
-                .sum::<u32>()
-                .to_string(),
-        )
+    fn part_2(&self) -> Result<Option<String>> {
+        let sum = self
+            .data
+            .maybe_par_iter()
+            .map(|d| CubeSet::power(d))
+            .sequential()
+            .try_checked_sum()?;
+
+        Ok(Some(sum.to_string()))
     }
 
     fn get_day_name(&self) -> String {
         "Day 2: Cube Conundrum".into()
     }
+
+    fn solver_version(&self) -> &'static str {
+        "1"
+    }
+
+    fn inspect(&self, stats: &mut Stats) {
+        stats.fact("games", self.data.len());
+        stats.range("draws per game", self.data.iter().map(|g| g.len()));
+        let draws = || self.data.iter().flatten();
+        stats.range("red", draws().map(|c| c.red));
+        stats.range("green", draws().map(|c| c.green));
+        stats.range("blue", draws().map(|c| c.blue));
+        let powers = self
+            .data
+            .iter()
+            .map(|game| {
+                let max = |colour: fn(&CubeSet) -> u32| {
+                    game.iter().map(colour).max().unwrap_or(0) as u128
+                };
+                max(|c| c.red) * max(|c| c.green) * max(|c| c.blue)
+            })
+            .collect::<Vec<_>>();
+        stats.fits::<u64, _>("largest power", powers.iter().max().copied().unwrap_or(0));
+        stats.fits::<u64, _>("sum of powers", powers.iter().sum::<u128>());
+    }
+
+    fn generate_input(&self, rng: &mut Rng, size: usize) -> String {
+        generator::generate(rng, size)
+    }
+
+    fn reference_answers(&self, input: &str) -> (Option<String>, Option<String>) {
+        (
+            Some(oracle::part_1(input).to_string()),
+            Some(oracle::part_2(input).to_string()),
+        )
+    }
 }
 
 struct CubeSet {
@@ -66,7 +115,7 @@ impl CubeSet {
         self.red <= 12 && self.green <= 13 && self.blue <= 14
     }
 
-    pub fn power(days: &[Self]) -> u32 {
+    pub fn power(days: &[Self]) -> Result<u64> {
         let mut red_max = 0;
         let mut green_max = 0;
         let mut blue_max = 0;
@@ -77,35 +126,55 @@ impl CubeSet {
             blue_max = blue_max.max(d.blue);
         }
 
-        red_max * green_max * blue_max
+        [red_max, green_max, blue_max]
+            .map(u64::from)
+            .into_iter()
+            .checked_product()
     }
 }
 
 impl FromStr for CubeSet {
-    type Err = !;
+    type Err = Report;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let split = s.split(", ");
+        parse_all(s, parse_cube_set)
+    }
+}
 
+impl TryFrom<Vec<(u32, &str)>> for CubeSet {
+    type Error = Report;
+
+    fn try_from(cubes: Vec<(u32, &str)>) -> Result<Self, Self::Error> {
         let mut red = 0;
         let mut blue = 0;
         let mut green = 0;
 
-        for s in split {
-            let s = s.trim();
-            let (value, key) = (&s[..s.find(' ').unwrap()], &s[(s.find(' ').unwrap() + 1)..]);
-            match key {
-                "red" => red += value.parse::<u32>().unwrap(),
-                "green" => green += value.parse::<u32>().unwrap(),
-                "blue" => blue += value.parse::<u32>().unwrap(),
-                _ => unreachable!(),
-            }
+        for (value, key) in cubes {
+            let count = match key {
+                "red" => &mut red,
+                "green" => &mut green,
+                "blue" => &mut blue,
+                _ => return Err(eyre!("unknown cube colour {:?}", key)),
+            };
+            *count = u32::checked_add(*count, value).or_overflow("a cube count")?;
         }
 
         Ok(Self { red, green, blue })
     }
 }
 
+fn parse_cube_set(i: &str) -> IResult<&str, CubeSet> {
+    map_res(
+        records(key_value(unsigned, " ", alpha1), ','),
+        CubeSet::try_from,
+    )(i)
+}
+
+fn parse_game(i: &str) -> IResult<&str, Vec<CubeSet>> {
+    let header = tuple((tag("Game"), space1, unsigned::<u32>, char(':')));
+    preceded(header, separated_list1(char(';'), parse_cube_set))(i)
+}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -121,9 +190,9 @@ Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
 
         let mut day = super::Day02::default();
-        day.parse_input(input);
+        day.parse_input(input).unwrap();
 
-        assert_eq!(day.part_1(), Some("8".into()));
+        assert_eq!(day.part_1().unwrap(), Some("8".into()));
     }
 
     #[test]
@@ -135,8 +204,8 @@ Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
 
         let mut day = super::Day02::default();
-        day.parse_input(input);
+        day.parse_input(input).unwrap();
 
-        assert_eq!(day.part_2(), Some("2286".into()));
+        assert_eq!(day.part_2().unwrap(), Some("2286".into()));
     }
 }
@@ -0,0 +1,34 @@
+//! Reference answers computed from the largest draw of each colour in every game.
+
+/// The largest number of cubes of each colour seen in any draw of every game.
+fn maxima(input: &str) -> Vec<(u64, [u64; 3])> {
+    input
+        .lines()
+        .map(|l| {
+            let (game, draws) = l.split_once(": ").unwrap();
+            let id = game.trim_start_matches("Game ").parse().unwrap();
+            let mut max = [0; 3];
+            for cubes in draws.split([';', ',']) {
+                let (n, colour) = cubes.trim().split_once(' ').unwrap();
+                let i = ["red", "green", "blue"]
+                    .iter()
+                    .position(|c| *c == colour)
+                    .unwrap();
+                max[i] = max[i].max(n.parse().unwrap());
+            }
+            (id, max)
+        })
+        .collect()
+}
+
+pub fn part_1(input: &str) -> u64 {
+    maxima(input)
+        .iter()
+        .filter(|(_, [r, g, b])| *r <= 12 && *g <= 13 && *b <= 14)
+        .map(|(id, _)| id)
+        .sum()
+}
+
+pub fn part_2(input: &str) -> u64 {
+    maxima(input).iter().map(|(_, [r, g, b])| r * g * b).sum()
+}
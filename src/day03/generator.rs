@@ -0,0 +1,40 @@
+use crate::rng::Rng;
+
+const SYMBOLS: [char; 8] = ['*', '#', '+', '$', '/', '=', '%', '@'];
+
+/// A `size` by `size` schematic with numbers of up to three digits scattered around, and
+/// symbols (mostly gears) dropped into the gaps.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    let mut grid = vec![vec!['.'; size]; size];
+
+    for row in grid.iter_mut() {
+        let mut x = rng.index(3);
+        while x < size {
+            if rng.chance(1, 2) {
+                let len = (rng.range_inclusive(1..=3) as usize).min(size - x);
+                for (i, cell) in row[x..x + len].iter_mut().enumerate() {
+                    // No leading zeros, the answer would not depend on them anyway.
+                    let lowest = if i == 0 { 1 } else { 0 };
+                    *cell = char::from(b'0' + rng.range(lowest..10) as u8);
+                }
+                x += len;
+            } else {
+                if rng.chance(1, 3) {
+                    row[x] = if rng.chance(1, 2) {
+                        '*'
+                    } else {
+                        *rng.choose(&SYMBOLS)
+                    };
+                }
+                x += 1;
+            }
+            // Numbers on the same row are always separated by at least one cell.
+            x += 1;
+        }
+    }
+
+    grid.iter()
+        .map(|row| row.iter().collect::<String>() + "\n")
+        .collect()
+}
@@ -1,28 +1,38 @@
 use std::str::FromStr;
 
+use color_eyre::eyre::Result;
+use color_eyre::Report;
 use itertools::Itertools;
 
+use crate::arith::{OrOverflow, TryCheckedIterator};
+use crate::inspect::Stats;
+use crate::parsing::char_grid;
+use crate::rng::Rng;
 use crate::AoCProblem;
 
+pub mod generator;
+pub mod oracle;
+
 #[derive(Default)]
 pub struct Day03 {
     data: EngineSchematic,
 }
 
 impl AoCProblem for Day03 {
-    fn parse_input(&mut self, input: &str) {
-        self.data = input.parse().unwrap();
+    fn parse_input(&mut self, input: &str) -> Result<()> {
+        self.data = input.parse()?;
+        Ok(())
     }
 
-    fn parse_input_default(&mut self) {
-        self.parse_input(include_str!("../../inputs/day03.txt"));
+    fn puzzle_input(&self) -> &'static str {
+        include_str!("../../inputs/day03.txt")
     }
 
-    fn part_1(&self) -> Option<String> {
+    fn part_1(&self) -> Result<Option<String>> {
         let symbols = self.data.get_symbols();
         let sum = symbols
             .iter()
-            .map(|(x, y)| {
+            .flat_map(|(x, y)| {
                 [
                     (0, 1),
                     (1, 0),
@@ -36,16 +46,17 @@ impl AoCProblem for Day03 {
                 .iter()
                 .filter(|(dx, dy)| self.data.get_or_default(x + dx, y + dy).is_ascii_digit())
                 .map(|(dx, dy)| self.data.find_number_bounds(x + dx, y + dy).unwrap())
-                .unique()
-                .map(|(st, en)| char_slice_to_int(&self.data.map[st..=en]))
-                .sum::<i32>()
+                .collect_vec()
             })
-            .sum::<i32>();
+            // A number next to two symbols is still only one part number.
+            .unique()
+            .map(|(st, en)| char_slice_to_int(&self.data.map[st..=en]))
+            .try_checked_sum()?;
 
-        Some(sum.to_string())
+        Ok(Some(sum.to_string()))
     }
 
-    fn part_2(&self) -> Option<String> {
+    fn part_2(&self) -> Result<Option<String>> {
         let symbols = self.data.get_gear_symbols();
         let sum = symbols
             .iter()
@@ -71,22 +82,64 @@ impl AoCProblem for Day03 {
                 unique
                     .iter()
                     .map(|(start, end)| char_slice_to_int(&self.data.map[*start..=*end]))
-                    .product::<i32>()
+                    .try_checked_product()
             })
-            .sum::<i32>();
+            .try_checked_sum()?;
 
-        Some(sum.to_string())
+        Ok(Some(sum.to_string()))
     }
 
     fn get_day_name(&self) -> String {
         "Day 03: Gear Ratios".into()
     }
+
+    fn solver_version(&self) -> &'static str {
+        "1"
+    }
+
+    fn inspect(&self, stats: &mut Stats) {
+        let data = &self.data;
+        stats.fact("grid", format!("{} x {}", data.width, data.height));
+        let numbers = data
+            .map
+            .chunks(data.width.max(1))
+            .flat_map(|row| row.split(|c| !c.is_ascii_digit()))
+            .filter(|digits| !digits.is_empty())
+            .collect_vec();
+        stats.fact("numbers", numbers.len());
+        stats.fact("symbols", data.get_symbols().len());
+        stats.fact("gear symbols", data.get_gear_symbols().len());
+        stats.range("digits per number", numbers.iter().map(|n| n.len()));
+        if let Some(longest) = numbers.iter().max_by_key(|n| n.len()) {
+            match char_slice_to_int(longest) {
+                Ok(n) => stats.fact("largest number", n),
+                Err(_) => stats.overflow(format!(
+                    "a number has {} digits, more than a u64 holds",
+                    longest.len()
+                )),
+            }
+        }
+    }
+
+    fn generate_input(&self, rng: &mut Rng, size: usize) -> String {
+        generator::generate(rng, size)
+    }
+
+    fn reference_answers(&self, input: &str) -> (Option<String>, Option<String>) {
+        (
+            Some(oracle::part_1(input).to_string()),
+            Some(oracle::part_2(input).to_string()),
+        )
+    }
 }
 
-fn char_slice_to_int(slice: &[char]) -> i32 {
-    slice.iter().rev().enumerate().fold(0, |acc, (i, c)| {
-        acc + (*c as i32 - '0' as i32) * 10_i32.pow(i as u32)
-    })
+fn char_slice_to_int(slice: &[char]) -> Result<u64> {
+    slice
+        .iter()
+        .try_fold(0_u64, |acc, c| {
+            acc.checked_mul(10)?.checked_add(c.to_digit(10)? as u64)
+        })
+        .or_overflow(format!("part number {:?}", String::from_iter(slice)))
 }
 
 #[derive(Default)]
@@ -97,47 +150,47 @@ struct EngineSchematic {
 }
 
 impl EngineSchematic {
-    fn get(&self, x: i32, y: i32) -> Option<char> {
-        if x >= self.width as i32 || y >= self.height as i32 || x < 0 || y < 0 {
+    fn get(&self, x: i64, y: i64) -> Option<char> {
+        if x >= self.width as i64 || y >= self.height as i64 || x < 0 || y < 0 {
             None
         } else {
-            Some(self.map[(y * self.width as i32 + x) as usize])
+            Some(self.map[(y * self.width as i64 + x) as usize])
         }
     }
 
-    fn get_or_default(&self, x: i32, y: i32) -> char {
+    fn get_or_default(&self, x: i64, y: i64) -> char {
         self.get(x, y).unwrap_or('.')
     }
 
-    const fn index(&self, x: i32, y: i32) -> Option<usize> {
-        if x >= self.width as i32 || y >= self.height as i32 || x < 0 || y < 0 {
+    const fn index(&self, x: i64, y: i64) -> Option<usize> {
+        if x >= self.width as i64 || y >= self.height as i64 || x < 0 || y < 0 {
             None
         } else {
-            Some((y * self.width as i32 + x) as usize)
+            Some((y * self.width as i64 + x) as usize)
         }
     }
 
-    fn get_symbols(&self) -> Vec<(i32, i32)> {
+    fn get_symbols(&self) -> Vec<(i64, i64)> {
         let mut symbols = Vec::new();
         for y in 0..self.height {
             for x in 0..self.width {
                 if self.map[y * self.width + x] != '.'
                     && !self.map[y * self.width + x].is_ascii_digit()
                 {
-                    symbols.push((x as i32, y as i32));
+                    symbols.push((x as i64, y as i64));
                 }
             }
         }
         symbols
     }
 
-    fn get_gear_symbols(&self) -> Vec<(i32, i32)> {
+    fn get_gear_symbols(&self) -> Vec<(i64, i64)> {
         self.map
             .iter()
             .enumerate()
             .filter_map(|(i, c)| {
                 if *c == '*' {
-                    Some(((i % self.width) as i32, (i / self.width) as i32))
+                    Some(((i % self.width) as i64, (i / self.width) as i64))
                 } else {
                     None
                 }
@@ -145,7 +198,7 @@ impl EngineSchematic {
             .collect_vec()
     }
 
-    fn find_number_bounds(&self, x: i32, y: i32) -> Option<(usize, usize)> {
+    fn find_number_bounds(&self, x: i64, y: i64) -> Option<(usize, usize)> {
         let mut index_start = self.index(x, y)?;
         let mut index_end = self.index(x, y)?;
         if !self.map[index_start].is_ascii_digit() {
@@ -171,22 +224,16 @@ impl EngineSchematic {
 }
 
 impl FromStr for EngineSchematic {
-    type Err = !;
+    type Err = Report;
 
     fn from_str(value: &str) -> Result<Self, Self::Err> {
-        let lines = value
-            .lines()
-            .map(|l| l.trim())
-            .filter(|l| !l.is_empty())
-            .collect_vec();
-        let height = lines.len();
-        let width = lines[0].len();
-        let mut map = Vec::with_capacity(width * height);
-        for line in lines {
-            map.extend(line.trim().chars());
-        }
+        let grid = char_grid(value)?;
 
-        Ok(Self { map, width, height })
+        Ok(Self {
+            map: grid.cells,
+            width: grid.width,
+            height: grid.height,
+        })
     }
 }
 
@@ -213,8 +260,8 @@ mod tests {
 ";
 
         let mut problem = super::Day03::default();
-        problem.parse_input(input);
-        assert_eq!(problem.part_1(), Some("4381".into()));
+        problem.parse_input(input).unwrap();
+        assert_eq!(problem.part_1().unwrap(), Some("4381".into()));
     }
 
     #[test]
@@ -234,7 +281,33 @@ mod tests {
 ";
 
         let mut problem = super::Day03::default();
-        problem.parse_input(input);
-        assert_eq!(problem.part_2(), Some("467835".into()));
+        problem.parse_input(input).unwrap();
+        assert_eq!(problem.part_2().unwrap(), Some("467835".into()));
+    }
+
+    #[test]
+    pub fn number_next_to_two_symbols() {
+        let mut problem = super::Day03::default();
+        // One part number, however many symbols it touches.
+        problem.parse_input("*12#\n....\n..7.\n.$.@\n").unwrap();
+        assert_eq!(problem.part_1().unwrap(), Some("19".into()));
+
+        // Two part numbers that happen to be the same.
+        problem.parse_input("12*12\n").unwrap();
+        assert_eq!(problem.part_1().unwrap(), Some("24".into()));
+    }
+
+    #[test]
+    pub fn overflow() {
+        let mut problem = super::Day03::default();
+        problem
+            .parse_input("12345678901234567890123*\n........................")
+            .unwrap();
+        assert!(problem.part_1().is_err());
+
+        problem
+            .parse_input("4294967296*4294967296\n.....................")
+            .unwrap();
+        assert!(problem.part_2().is_err());
     }
 }
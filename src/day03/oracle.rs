@@ -0,0 +1,71 @@
+//! Reference answers that check every number against every symbol in the schematic.
+
+/// Every number in the schematic as (row, first column, last column, value).
+fn numbers(grid: &[Vec<char>]) -> Vec<(usize, usize, usize, u64)> {
+    let mut found = Vec::new();
+    for (y, row) in grid.iter().enumerate() {
+        let mut x = 0;
+        while x < row.len() {
+            if row[x].is_ascii_digit() {
+                let start = x;
+                while x < row.len() && row[x].is_ascii_digit() {
+                    x += 1;
+                }
+                let value = row[start..x].iter().collect::<String>().parse().unwrap();
+                found.push((y, start, x - 1, value));
+            } else {
+                x += 1;
+            }
+        }
+    }
+    found
+}
+
+fn touches(number: &(usize, usize, usize, u64), x: usize, y: usize) -> bool {
+    let (row, start, end, _) = *number;
+    y + 1 >= row && y <= row + 1 && x + 1 >= start && x <= end + 1
+}
+
+fn grid(input: &str) -> Vec<Vec<char>> {
+    input
+        .lines()
+        .filter(|l| !l.is_empty())
+        .map(|l| l.chars().collect())
+        .collect()
+}
+
+pub fn part_1(input: &str) -> u64 {
+    let grid = grid(input);
+    numbers(&grid)
+        .iter()
+        .filter(|n| {
+            grid.iter().enumerate().any(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .any(|(x, c)| *c != '.' && !c.is_ascii_digit() && touches(n, x, y))
+            })
+        })
+        .map(|n| n.3)
+        .sum()
+}
+
+pub fn part_2(input: &str) -> u64 {
+    let grid = grid(input);
+    let numbers = numbers(&grid);
+    let mut sum = 0;
+    for (y, row) in grid.iter().enumerate() {
+        for (x, c) in row.iter().enumerate() {
+            if *c != '*' {
+                continue;
+            }
+            let adjacent = numbers
+                .iter()
+                .filter(|n| touches(n, x, y))
+                .collect::<Vec<_>>();
+            if adjacent.len() == 2 {
+                sum += adjacent[0].3 * adjacent[1].3;
+            }
+        }
+    }
+    sum
+}
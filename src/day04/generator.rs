@@ -0,0 +1,39 @@
+use crate::rng::Rng;
+
+/// `size` cards with five winning numbers and eight scratched ones, all distinct within a
+/// list. No card wins copies of cards past the end of the table, and three cards in four win
+/// nothing: with more than one match per card on average the number of copies grows
+/// exponentially and part 2 overflows long before a million cards.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for card in 1..=size {
+        let mut pool = (1..100).collect::<Vec<u64>>();
+        rng.shuffle(&mut pool);
+        let winning = &pool[..5];
+
+        let max_matches = (size - card).min(5);
+        let matches = if rng.chance(3, 4) {
+            0
+        } else {
+            rng.range_inclusive(0..=max_matches as u64) as usize
+        };
+        let mut scratch = winning[..matches].to_vec();
+        scratch.extend(&pool[5..5 + 8 - matches]);
+        rng.shuffle(&mut scratch);
+
+        let list = |numbers: &[u64]| {
+            numbers
+                .iter()
+                .map(|n| format!("{:>2}", n))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        out.push_str(&format!(
+            "Card {:>3}: {} | {}\n",
+            card,
+            list(winning),
+            list(&scratch)
+        ));
+    }
+    out
+}
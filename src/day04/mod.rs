@@ -1,100 +1,252 @@
-use crate::AoCProblem;
-use itertools::Itertools;
+use crate::arith::{OrOverflow, TryCheckedIterator};
+use crate::inspect::Stats;
+use crate::memo::DenseMemo;
+use crate::parallel::prelude::*;
+use crate::parsing::{parse_all, parse_lines, unsigned, unsigned_list};
+use crate::rng::Rng;
+use crate::stream::LineSolver;
+use crate::{unknown_variant, AoCProblem};
+use color_eyre::eyre::Result;
+use color_eyre::Report;
+use nom::bytes::complete::tag;
+use nom::character::complete::{char, space0, space1};
+use nom::sequence::{preceded, separated_pair, tuple};
+use nom::IResult;
+use std::collections::VecDeque;
 use std::str::FromStr;
 
+pub mod generator;
+pub mod oracle;
+
 #[derive(Default)]
 pub struct Day04 {
     data: Vec<ScratchCard>,
+    variant: Variant,
+}
+
+#[derive(Default, Clone, Copy)]
+enum Variant {
+    /// Counts the copies each card is worth, last card first.
+    #[default]
+    Dp,
+    /// Hands out every single copy through a queue, the way the puzzle describes it.
+    Queue,
 }
 
 impl AoCProblem for Day04 {
-    fn parse_input(&mut self, input: &str) {
-        self.data = input.lines().map(|l| l.parse().unwrap()).collect_vec();
+    fn parse_input(&mut self, input: &str) -> Result<()> {
+        self.data = parse_lines(input)?;
+        Ok(())
     }
 
-    fn parse_input_default(&mut self) {
-        self.parse_input(include_str!("../../inputs/day04.txt"));
+    fn puzzle_input(&self) -> &'static str {
+        include_str!("../../inputs/day04.txt")
     }
 
-    fn part_1(&self) -> Option<String> {
-        let winning_cards: u32 = self.data.iter().map(|c| c.get_card_value()).sum();
-        Some(winning_cards.to_string())
+    fn part_1(&self) -> Result<Option<String>> {
+        let winning_cards = self
+            .data
+            .maybe_par_iter()
+            .map(|c| c.get_card_value())
+            .sequential()
+            .try_checked_sum()?;
+        Ok(Some(winning_cards.to_string()))
     }
 
-    fn part_2(&self) -> Option<String> {
-        let mut store = (0..self.data.len()).map(|_| 1).collect_vec();
+    fn part_2(&self) -> Result<Option<String>> {
+        let rewards = self
+            .data
+            .maybe_par_iter()
+            .map(ScratchCard::matches)
+            .collect::<Vec<_>>();
+        if let Variant::Queue = self.variant {
+            return Ok(Some(cards_queued(&rewards)?.to_string()));
+        }
+
+        // Walking backwards means every card's rewards are already cached when it is reached,
+        // so the recursion never goes more than one level deep.
+        let mut memo = DenseMemo::new();
+        let total_cards = (0..self.data.len())
+            .rev()
+            .map(|card| cards_won(&mut memo, &rewards, card))
+            .try_fold(0_u64, |acc, won| acc.checked_add(won?))
+            .or_overflow("the number of cards")?;
 
+        Ok(Some(total_cards.to_string()))
+    }
+
+    fn get_day_name(&self) -> String {
+        "Day 04: Scratchcards".into()
+    }
+
+    fn solver_version(&self) -> &'static str {
+        "1"
+    }
+
+    fn inspect(&self, stats: &mut Stats) {
+        stats.fact("cards", self.data.len());
+        stats.range("winning numbers", self.data.iter().map(|c| c.winning.len()));
+        stats.range(
+            "scratched numbers",
+            self.data.iter().map(|c| c.scratch.len()),
+        );
         let rewards = self
             .data
             .iter()
-            .map(|sc| sc.scratch.iter().filter(|n| sc.winning.contains(n)).count())
-            .collect_vec();
-
-        let mut to_process = (0..self.data.len()).collect_vec();
-        while let Some(card) = to_process.pop() {
-            let reward = rewards[card];
-            for next_card in card + 1..=card + reward {
-                store[next_card] += 1;
+            .map(ScratchCard::matches)
+            .collect::<Vec<_>>();
+        stats.range("matches", rewards.iter().copied());
 
-                to_process.push(next_card);
+        let best = rewards.iter().max().copied().unwrap_or(0);
+        let points = if best == 0 {
+            0
+        } else {
+            1_u128.checked_shl(best as u32 - 1).unwrap_or(u128::MAX)
+        };
+        stats.fits::<u64, _>("most points on a card", points);
+        let mut copies = vec![1_u128; rewards.len()];
+        for (card, &won) in rewards.iter().enumerate() {
+            for next in card + 1..=(card + won).min(rewards.len() - 1) {
+                copies[next] = copies[next].saturating_add(copies[card]);
             }
         }
+        let cards = copies.iter().fold(0_u128, |acc, &c| acc.saturating_add(c));
+        stats.fits::<u64, _>("cards won", cards);
+    }
 
-        let total_cards = store.iter().sum::<u64>();
+    fn generate_input(&self, rng: &mut Rng, size: usize) -> String {
+        generator::generate(rng, size)
+    }
 
-        Some(total_cards.to_string())
+    fn line_solver(&self) -> Option<Box<dyn LineSolver>> {
+        Some(Box::<Streaming>::default())
     }
 
-    fn get_day_name(&self) -> String {
-        "Day 04: Scratchcards".into()
+    fn variants(&self) -> &'static [&'static str] {
+        &["dp", "queue"]
+    }
+
+    fn select_variant(&mut self, name: &str) -> Result<()> {
+        self.variant = match name {
+            "dp" => Variant::Dp,
+            "queue" => Variant::Queue,
+            _ => return Err(unknown_variant(self, name)),
+        };
+        Ok(())
+    }
+
+    fn reference_answers(&self, input: &str) -> (Option<String>, Option<String>) {
+        (
+            Some(oracle::part_1(input).to_string()),
+            Some(oracle::part_2(input).to_string()),
+        )
+    }
+}
+
+/// The card itself plus every copy it wins, directly or through the copies, `None` if that
+/// does not fit in a `u64`.
+fn cards_won(memo: &mut DenseMemo<Option<u64>>, rewards: &[usize], card: usize) -> Option<u64> {
+    memo.get_or_insert_with(card, |memo| {
+        let won = card + 1..=(card + rewards[card]).min(rewards.len() - 1);
+        won.map(|next| cards_won(memo, rewards, next))
+            .try_fold(1_u64, |acc, won| acc.checked_add(won?))
+    })
+}
+
+/// Takes one card at a time off a queue and queues up the copies it wins. Takes as long as
+/// the answer is large.
+fn cards_queued(rewards: &[usize]) -> Result<u64> {
+    let mut queue = (0..rewards.len()).collect::<VecDeque<_>>();
+    let mut total = 0_u64;
+    while let Some(card) = queue.pop_front() {
+        total = total.checked_add(1).or_overflow("the number of cards")?;
+        queue.extend(card + 1..=(card + rewards[card]).min(rewards.len() - 1));
+    }
+    Ok(total)
+}
+
+/// Part 2 in order from the first card: each card's copies are known by the time it is read,
+/// and only the copies it hands to the next few cards have to be remembered.
+#[derive(Default)]
+struct Streaming {
+    points: u64,
+    cards: u64,
+    pending: VecDeque<u64>,
+}
+
+impl LineSolver for Streaming {
+    fn feed(&mut self, line: &str) -> Result<()> {
+        if line.trim().is_empty() {
+            return Ok(());
+        }
+        let card = line.parse::<ScratchCard>()?;
+        self.points = self
+            .points
+            .checked_add(card.get_card_value()?)
+            .or_overflow("a sum")?;
+
+        let copies = 1 + self.pending.pop_front().unwrap_or(0);
+        self.cards = self
+            .cards
+            .checked_add(copies)
+            .or_overflow("the number of cards")?;
+        let won = card.matches();
+        if self.pending.len() < won {
+            self.pending.resize(won, 0);
+        }
+        for next in self.pending.iter_mut().take(won) {
+            *next = next
+                .checked_add(copies)
+                .or_overflow("the number of cards")?;
+        }
+        Ok(())
+    }
+
+    fn finish(self: Box<Self>) -> Result<(Option<String>, Option<String>)> {
+        Ok((Some(self.points.to_string()), Some(self.cards.to_string())))
     }
 }
 
 struct ScratchCard {
-    winning: Vec<u8>,
-    scratch: Vec<u8>,
+    winning: Vec<u32>,
+    scratch: Vec<u32>,
 }
 
 impl ScratchCard {
-    fn get_card_value(&self) -> u32 {
-        let pow = self
-            .scratch
+    fn matches(&self) -> usize {
+        self.scratch
             .iter()
             .filter(|n| self.winning.contains(n))
-            .count();
+            .count()
+    }
+
+    fn get_card_value(&self) -> Result<u64> {
+        let pow = self.matches();
         if pow == 0 {
-            return 0;
+            return Ok(0);
         }
-        2_u32.pow(pow as u32 - 1)
+        1_u64
+            .checked_shl(pow as u32 - 1)
+            .or_overflow(format!("the value of a card with {} matches", pow))
     }
 }
 
 impl FromStr for ScratchCard {
-    type Err = !;
+    type Err = Report;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let card_number_end = s.find(": ").unwrap();
-        let mut parts = s[card_number_end + 2..].split(" | ");
-
-        let scratch = parts
-            .next()
-            .unwrap()
-            .split(' ')
-            .filter(|c| !c.is_empty())
-            .map(|n| n.parse().unwrap())
-            .collect();
-        let winning = parts
-            .next()
-            .unwrap()
-            .split(' ')
-            .filter(|c| !c.is_empty())
-            .map(|n| n.parse().unwrap())
-            .collect();
-
-        Ok(Self { winning, scratch })
+        parse_all(s, parse_card)
     }
 }
 
+fn parse_card(i: &str) -> IResult<&str, ScratchCard> {
+    let header = tuple((tag("Card"), space1, unsigned::<u32>, char(':')));
+    let numbers = separated_pair(unsigned_list, tuple((space0, char('|'))), unsigned_list);
+    let (i, (winning, scratch)) = preceded(header, numbers)(i)?;
+
+    Ok((i, ScratchCard { winning, scratch }))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::AoCProblem;
@@ -109,8 +261,8 @@ Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
         let mut day04 = super::Day04::default();
-        day04.parse_input(input);
-        assert_eq!(day04.part_1(), Some("13".into()));
+        day04.parse_input(input).unwrap();
+        assert_eq!(day04.part_1().unwrap(), Some("13".into()));
     }
 
     #[test]
@@ -122,7 +274,22 @@ Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
         let mut day04 = super::Day04::default();
-        day04.parse_input(input);
-        assert_eq!(day04.part_2(), Some("30".into()));
+        day04.parse_input(input).unwrap();
+        assert_eq!(day04.part_2().unwrap(), Some("30".into()));
+    }
+
+    #[test]
+    fn variants_agree() {
+        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+        let mut day04 = super::Day04::default();
+        day04.parse_input(input).unwrap();
+        day04.select_variant("queue").unwrap();
+        assert_eq!(day04.part_2().unwrap(), Some("30".into()));
+        assert!(day04.select_variant("stack").is_err());
     }
 }
@@ -0,0 +1,34 @@
+//! Reference answers, part 2 scratches every single copy of every card one at a time.
+
+fn matches(input: &str) -> Vec<usize> {
+    input
+        .lines()
+        .map(|l| {
+            let (_, numbers) = l.split_once(':').unwrap();
+            let (left, right) = numbers.split_once('|').unwrap();
+            let left = left.split_whitespace().collect::<Vec<_>>();
+            right
+                .split_whitespace()
+                .filter(|n| left.contains(n))
+                .count()
+        })
+        .collect()
+}
+
+pub fn part_1(input: &str) -> u64 {
+    matches(input)
+        .iter()
+        .map(|&m| if m == 0 { 0 } else { 1 << (m - 1) })
+        .sum()
+}
+
+pub fn part_2(input: &str) -> u64 {
+    let matches = matches(input);
+    let mut pile = (0..matches.len()).collect::<Vec<_>>();
+    let mut scratched = 0;
+    while let Some(card) = pile.pop() {
+        scratched += 1;
+        pile.extend(card + 1..=card + matches[card]);
+    }
+    scratched
+}
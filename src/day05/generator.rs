@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+
+use crate::rng::Rng;
+
+const CATEGORIES: [&str; 8] = [
+    "seed",
+    "soil",
+    "fertilizer",
+    "water",
+    "light",
+    "temperature",
+    "humidity",
+    "location",
+];
+
+/// An almanac with `size` seed pairs and the usual seven maps of up to `size` mappings each.
+/// Within a map neither the source nor the destination ranges overlap, and seeds are small
+/// enough that the (start, length) reading of part 2 stays cheap to brute force.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    let limit = 100 * size as u64;
+
+    let seeds = (0..size)
+        .flat_map(|_| [rng.below(limit), rng.range_inclusive(1..=10)])
+        .map(|n| n.to_string())
+        .collect::<Vec<_>>();
+    let mut out = format!("seeds: {}\n", seeds.join(" "));
+
+    for names in CATEGORIES.windows(2) {
+        out.push_str(&format!("\n{}-to-{} map:\n", names[0], names[1]));
+
+        // Cut 0..limit into pieces, then lay the same pieces out again in a different order to
+        // get the destinations.
+        let mut cuts = (0..rng.range_inclusive(1..=size as u64))
+            .map(|_| rng.below(limit))
+            .chain([0, limit])
+            .collect::<Vec<_>>();
+        cuts.sort_unstable();
+        cuts.dedup();
+        let mut pieces = cuts
+            .windows(2)
+            .map(|w| (w[0], w[1] - w[0]))
+            .collect::<Vec<_>>();
+        let mut destinations = pieces.clone();
+        rng.shuffle(&mut destinations);
+        let shift = rng.below(limit);
+        let mut next = shift;
+        let mut placed = HashMap::new();
+        for (start, len) in destinations {
+            placed.insert(start, next);
+            next += len;
+        }
+
+        rng.shuffle(&mut pieces);
+        for (source, len) in pieces {
+            // Some pieces are left out so the identity gaps are exercised too.
+            if rng.chance(1, 4) {
+                continue;
+            }
+            let destination = placed[&source];
+            out.push_str(&format!("{} {} {}\n", destination, source, len));
+        }
+    }
+    out
+}
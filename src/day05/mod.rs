@@ -1,84 +1,358 @@
+use std::collections::HashMap;
+use std::ops::Range;
 use std::str::FromStr;
 
-use itertools::Itertools;
+use color_eyre::eyre::{eyre, Result, WrapErr};
+use color_eyre::Report;
+use nom::character::complete::space1;
+use nom::sequence::tuple;
+use nom::IResult;
 
-use crate::AoCProblem;
+use crate::arith::OrOverflow;
+use crate::graph::{bfs_path, strongly_connected_components, AdjacencyList};
+use crate::inspect::Stats;
+use crate::parallel::prelude::*;
+use crate::parsing::{blocks, labelled, parse_all, parse_lines, unsigned, unsigned_list};
+use crate::reduce::{self, Chunk};
+use crate::rng::Rng;
+use crate::{unknown_variant, AoCProblem};
+
+use self::piecewise::Piecewise;
+
+pub mod generator;
+pub mod oracle;
+pub mod piecewise;
 
 #[derive(Default)]
 pub struct Day05 {
     seeds: Vec<u64>,
     maps: Vec<SeedMap>,
+    /// Which map to take from each category to the next.
+    categories: AdjacencyList<String>,
+    by_categories: HashMap<(String, String), usize>,
+    /// The maps on the way from seed to location, as one.
+    composed: Piecewise,
+    variant: Variant,
 }
 
-impl AoCProblem for Day05 {
-    fn parse_input(&mut self, input: &str) {
-        let mut parts = input.split("\n\n");
+#[derive(Default, Clone, Copy)]
+enum Variant {
+    /// Finds the lowest location of each seed range.
+    #[default]
+    Forward,
+    /// Goes through the locations from the lowest up until one of them has a seed.
+    Reverse,
+}
 
-        let seeds = parts
-            .next()
-            .unwrap()
-            .strip_prefix("seeds: ")
-            .unwrap()
-            .split(' ')
-            .map(|s| s.parse::<u64>().unwrap())
-            .collect_vec();
-        let maps = parts.map(|p| p.parse::<SeedMap>().unwrap()).collect_vec();
+impl Day05 {
+    /// The whole chain of maps as one, from seed to location.
+    pub const fn composed(&self) -> &Piecewise {
+        &self.composed
+    }
 
-        self.seeds = seeds;
-        self.maps = maps;
+    /// The categories on the way through the maps from `from` to `to`, both included. There is
+    /// at most one, since no category has two maps from it.
+    pub fn path(&self, from: &str, to: &str) -> Result<Vec<String>> {
+        for category in [from, to] {
+            if !self.categories.contains(&category.to_string()) {
+                return Err(eyre!("no map goes from or to {:?}", category));
+            }
+        }
+        bfs_path(&self.categories, from.to_string(), |c| c == to)
+            .ok_or_else(|| eyre!("no maps lead from {:?} to {:?}", from, to))
     }
 
-    fn parse_input_default(&mut self) {
-        self.parse_input(include_str!("../../inputs/day05.txt"));
+    /// The maps from `from` to `to`, e.g. seed to humidity, as one.
+    pub fn route(&self, from: &str, to: &str) -> Result<Piecewise> {
+        let path = self.path(from, to)?;
+        Ok(path.windows(2).fold(Piecewise::identity(), |acc, step| {
+            let map = self.by_categories[&(step[0].clone(), step[1].clone())];
+            acc.then(&self.maps[map].piecewise())
+        }))
     }
 
-    fn part_1(&self) -> Option<String> {
-        let mut seeds = self.seeds.clone();
-        for map in &self.maps {
-            seeds = map.map_seeds(&seeds);
+    /// The seeds that are planted somewhere in `locations`.
+    pub fn seeds_landing_in(&self, locations: Range<u64>) -> Vec<Range<u64>> {
+        self.composed.preimage(locations)
+    }
+}
+
+/// The lowest location of any seed in `seeds`, looking at the pieces of `composed` in the order
+/// of their locations and stopping as soon as no later piece can do better.
+fn lowest_searching_up(composed: &Piecewise, mut seeds: Vec<Range<u64>>) -> Option<u64> {
+    seeds.retain(|r| !r.is_empty());
+    seeds.sort_unstable_by_key(|r| r.start);
+    // The furthest any range up to each one reaches, so a range inside another one does not
+    // hide the rest of the outer one.
+    let reach = seeds
+        .iter()
+        .scan(0, |reach, r| {
+            *reach = r.end.max(*reach);
+            Some(*reach)
+        })
+        .collect::<Vec<_>>();
+
+    let mut pieces = composed.pieces().collect::<Vec<_>>();
+    pieces.sort_unstable_by_key(|(_, target)| *target);
+
+    let mut lowest: Option<u64> = None;
+    for (source, target) in pieces {
+        if lowest.is_some_and(|l| l <= target) {
+            break;
         }
+        // The first range reaching into the piece holds its first seed.
+        let i = reach.partition_point(|&end| end <= source.start);
+        let Some(first) = seeds.get(i).map(|s| s.start.max(source.start)) else {
+            continue;
+        };
+        if first < source.end {
+            let location = target + (first - source.start);
+            lowest = Some(lowest.map_or(location, |l| l.min(location)));
+        }
+    }
+    lowest
+}
+
+impl AoCProblem for Day05 {
+    fn parse_input(&mut self, input: &str) -> Result<()> {
+        let parts = blocks(input);
+        let (seeds, maps) = parts.split_first().ok_or_else(|| eyre!("input is empty"))?;
+
+        self.seeds = parse_all(seeds, labelled("seeds:", unsigned_list))?;
+        self.maps = maps.iter().map(|p| p.parse()).collect::<Result<_>>()?;
+
+        self.categories = AdjacencyList::new();
+        self.by_categories = HashMap::new();
+        // Each category goes on to at most one other and comes from at most one, otherwise
+        // which way a seed goes would be up to the route taken.
+        let mut from = HashMap::new();
+        let mut to = HashMap::new();
+        for (i, map) in self.maps.iter().enumerate() {
+            let key = (map.source.clone(), map.destination.clone());
+            if self.by_categories.insert(key, i).is_some() {
+                return Err(eyre!("there are two {} maps", map.name()));
+            }
+            if let Some(other) = from.insert(&map.source, map) {
+                return Err(eyre!(
+                    "{} has two maps, {} and {}",
+                    map.source,
+                    other.name(),
+                    map.name()
+                ));
+            }
+            if let Some(other) = to.insert(&map.destination, map) {
+                return Err(eyre!(
+                    "{} has two maps to it, {} and {}",
+                    map.destination,
+                    other.name(),
+                    map.name()
+                ));
+            }
+            self.categories
+                .add_edge(map.source.clone(), map.destination.clone(), 1);
+        }
+        let cycle = strongly_connected_components(&self.categories)
+            .into_iter()
+            .find(|c| {
+                c.len() > 1
+                    || self
+                        .by_categories
+                        .contains_key(&(c[0].clone(), c[0].clone()))
+            });
+        if let Some(mut cycle) = cycle {
+            cycle.sort_unstable();
+            return Err(eyre!(
+                "the maps between {} go in a circle",
+                cycle.join(", ")
+            ));
+        }
+
+        self.composed = self.route("seed", "location")?;
+        Ok(())
+    }
 
-        Some(seeds.iter().min().unwrap().to_string())
+    fn puzzle_input(&self) -> &'static str {
+        include_str!("../../inputs/day05.txt")
     }
 
-    fn part_2(&self) -> Option<String> {
-        None
+    fn part_1(&self) -> Result<Option<String>> {
+        let lowest = self
+            .seeds
+            .maybe_par_iter()
+            .map(|&seed| self.composed.get(seed))
+            .min()
+            .ok_or_else(|| eyre!("no seeds to plant"))?;
+        Ok(Some(lowest.to_string()))
+    }
+
+    fn part_2(&self) -> Result<Option<String>> {
+        if !self.seeds.len().is_multiple_of(2) {
+            return Err(eyre!(
+                "{} seed numbers do not pair up into ranges",
+                self.seeds.len()
+            ));
+        }
+        let ranges = self
+            .seeds
+            .chunks(2)
+            .map(|pair| {
+                let end = pair[0]
+                    .checked_add(pair[1])
+                    .or_overflow(format!("the end of seed range {} {}", pair[0], pair[1]))?;
+                Ok(pair[0]..end)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let lowest = match self.variant {
+            Variant::Forward => ranges
+                .into_iter()
+                .filter_map(|range| self.composed.lowest_in(range))
+                .min(),
+            Variant::Reverse => lowest_searching_up(&self.composed, ranges),
+        }
+        .ok_or_else(|| eyre!("no seeds to plant"))?;
+        Ok(Some(lowest.to_string()))
     }
 
     fn get_day_name(&self) -> String {
         "Day 05: If You Give a Seed a Fertilizer".into()
     }
+
+    fn solver_version(&self) -> &'static str {
+        "5"
+    }
+
+    fn inspect(&self, stats: &mut Stats) {
+        stats.fact("seeds", self.seeds.len());
+        stats.range("seed", self.seeds.iter());
+        let ranges = self.seeds.chunks(2).filter(|r| r.len() == 2);
+        stats.fact(
+            "seeds in ranges",
+            ranges.clone().map(|r| r[1] as u128).sum::<u128>(),
+        );
+        stats.fits::<u64, _>(
+            "largest seed range end",
+            ranges
+                .map(|r| r[0] as u128 + r[1] as u128)
+                .max()
+                .unwrap_or(0),
+        );
+        stats.fact("maps", self.maps.len());
+        if let Ok(path) = self.path("seed", "location") {
+            stats.fact("route", path.join(" -> "));
+        }
+        stats.range(
+            "mappings per map",
+            self.maps.iter().map(|m| m.mappings.len()),
+        );
+        let mappings = || self.maps.iter().flat_map(|m| &m.mappings);
+        stats.range("range length", mappings().map(|m| m.range_length));
+        stats.fact("composed pieces", self.composed.breakpoints().count() + 1);
+        stats.fits::<u64, _>(
+            "largest range end",
+            mappings()
+                .flat_map(|m| [m.source_range_start, m.destination_range_start])
+                .zip(mappings().flat_map(|m| [m.range_length; 2]))
+                .map(|(start, len)| start as u128 + len as u128)
+                .max()
+                .unwrap_or(0),
+        );
+    }
+
+    fn generate_input(&self, rng: &mut Rng, size: usize) -> String {
+        generator::generate(rng, size)
+    }
+
+    fn variants(&self) -> &'static [&'static str] {
+        &["forward", "reverse"]
+    }
+
+    fn select_variant(&mut self, name: &str) -> Result<()> {
+        self.variant = match name {
+            "forward" => Variant::Forward,
+            "reverse" => Variant::Reverse,
+            _ => return Err(unknown_variant(self, name)),
+        };
+        Ok(())
+    }
+
+    fn reference_answers(&self, input: &str) -> (Option<String>, Option<String>) {
+        (
+            Some(oracle::part_1(input).to_string()),
+            oracle::part_2(input).map(|n| n.to_string()),
+        )
+    }
+
+    fn reduction_chunks<'a>(&self, input: &'a str) -> Vec<Chunk<'a>> {
+        // Only mapping lines, the seeds and map headers stay.
+        reduce::lines_where(input, |l| l.starts_with(|c: char| c.is_ascii_digit()))
+    }
 }
 
 struct SeedMap {
-    name: String,
+    source: String,
+    destination: String,
     mappings: Vec<Mapping>,
 }
 
 impl SeedMap {
-    fn map_seeds(&self, seeds: &[u64]) -> Vec<u64> {
-        seeds.iter().map(|s| self.map_seed(*s)).collect_vec()
+    fn name(&self) -> String {
+        format!("{}-to-{}", self.source, self.destination)
     }
 
-    fn map_seed(&self, seed: u64) -> u64 {
-        for mapping in &self.mappings {
-            if let Some(new_seed) = mapping.apply_mapping_to_seed(seed) {
-                return new_seed;
-            }
-        }
-        seed
+    fn piecewise(&self) -> Piecewise {
+        Piecewise::from_ranges(
+            self.mappings
+                .iter()
+                .map(|m| (m.source(), m.destination_range_start)),
+        )
     }
 }
 
 impl FromStr for SeedMap {
-    type Err = !;
+    type Err = Report;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut lines = s.lines();
-        let name = lines.next().unwrap().strip_suffix(':').unwrap().into();
-        let mappings = lines.map(|l| l.parse().unwrap()).collect_vec();
+        let (header, body) = s.split_once('\n').unwrap_or((s, ""));
+        let (source, destination) = header
+            .trim()
+            .strip_suffix(" map:")
+            .and_then(|name| name.split_once("-to-"))
+            .filter(|(source, destination)| !source.is_empty() && !destination.is_empty())
+            .ok_or_else(|| {
+                eyre!(
+                    "expected a header like \"seed-to-soil map:\", got {:?}",
+                    header
+                )
+            })?;
+        let map = Self {
+            source: source.into(),
+            destination: destination.into(),
+            mappings: Vec::new(),
+        };
+        let mut mappings =
+            parse_lines::<Mapping>(body).wrap_err_with(|| format!("in the {} map", map.name()))?;
+
+        mappings.sort_unstable_by_key(|m| m.source_range_start);
+        // An empty mapping maps nothing, so it can't overlap anything either.
+        let sources = mappings
+            .iter()
+            .map(Mapping::source)
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>();
+        for pair in sources.windows(2) {
+            let (a, b) = (&pair[0], &pair[1]);
+            if b.start < a.end {
+                return Err(eyre!(
+                    "the sources {:?} and {:?} of the {} map overlap",
+                    a,
+                    b,
+                    map.name()
+                ));
+            }
+        }
 
-        Ok(Self { name, mappings })
+        Ok(Self { mappings, ..map })
     }
 }
 
@@ -89,29 +363,41 @@ struct Mapping {
 }
 
 impl Mapping {
-    const fn apply_mapping_to_seed(&self, seed: u64) -> Option<u64> {
-        if seed >= self.source_range_start && seed < self.source_range_start + self.range_length {
-            Some(self.destination_range_start + (seed - self.source_range_start))
-        } else {
-            None
-        }
+    const fn source(&self) -> Range<u64> {
+        self.source_range_start..self.source_range_start + self.range_length
     }
 }
 
 impl FromStr for Mapping {
-    type Err = !;
+    type Err = Report;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split(' ');
+        let mapping = parse_all(s, parse_mapping)?;
+        // Checking the ends once here means applying a mapping can never overflow.
+        for start in [mapping.source_range_start, mapping.destination_range_start] {
+            start
+                .checked_add(mapping.range_length)
+                .or_overflow(format!("the end of mapping {:?}", s))?;
+        }
 
-        Ok(Self {
-            destination_range_start: parts.next().unwrap().parse().unwrap(),
-            source_range_start: parts.next().unwrap().parse().unwrap(),
-            range_length: parts.next().unwrap().parse().unwrap(),
-        })
+        Ok(mapping)
     }
 }
 
+fn parse_mapping(i: &str) -> IResult<&str, Mapping> {
+    let (i, (destination_range_start, _, source_range_start, _, range_length)) =
+        tuple((unsigned, space1, unsigned, space1, unsigned))(i)?;
+
+    Ok((
+        i,
+        Mapping {
+            destination_range_start,
+            source_range_start,
+            range_length,
+        },
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::AoCProblem;
@@ -152,8 +438,8 @@ humidity-to-location map:
 60 56 37
 56 93 4";
         let mut day05 = crate::day05::Day05::default();
-        day05.parse_input(input);
-        assert_eq!(day05.part_1(), Some("35".into()));
+        day05.parse_input(input).unwrap();
+        assert_eq!(day05.part_1().unwrap(), Some("35".into()));
     }
 
     #[test]
@@ -192,7 +478,156 @@ humidity-to-location map:
 60 56 37
 56 93 4";
         let mut day05 = crate::day05::Day05::default();
-        day05.parse_input(input);
-        // assert_eq!(day05.part_2(), Some("46".into()));
+        day05.parse_input(input).unwrap();
+        assert_eq!(day05.part_2().unwrap(), Some("46".into()));
+        assert!(day05.seeds_landing_in(46..47).contains(&(82..83)));
+        day05.select_variant("reverse").unwrap();
+        assert_eq!(day05.part_2().unwrap(), Some("46".into()));
+    }
+
+    #[test]
+    fn routes_by_category() {
+        let input = "seeds: 79 14 55 13
+
+humidity-to-location map:
+60 56 37
+56 93 4
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+";
+        let mut day05 = crate::day05::Day05::default();
+        day05.parse_input(input).unwrap();
+        assert_eq!(day05.part_1().unwrap(), Some("35".into()));
+        assert_eq!(day05.route("seed", "humidity").unwrap().get(79), 78);
+        assert_eq!(day05.route("water", "light").unwrap().get(81), 74);
+        assert_eq!(day05.path("seed", "soil").unwrap(), ["seed", "soil"]);
+        assert!(day05.route("soil", "seed").is_err());
+        assert!(day05.route("seed", "weather").is_err());
+
+        let error = |input: &str| {
+            crate::day05::Day05::default()
+                .parse_input(input)
+                .unwrap_err()
+                .root_cause()
+                .to_string()
+        };
+        let seed_to_location = "seed-to-location map:\n1 2 3\n";
+        assert_eq!(
+            error("seeds: 1\n\nseed-to-soil map:\n1 2 3\n"),
+            "no map goes from or to \"location\""
+        );
+        assert_eq!(
+            error(&format!(
+                "seeds: 1\n\n{}\n{}",
+                seed_to_location, seed_to_location
+            )),
+            "there are two seed-to-location maps"
+        );
+        assert_eq!(
+            error(&format!(
+                "seeds: 1\n\n{}\nsoil-to-water map:\n\nwater-to-soil map:\n",
+                seed_to_location
+            )),
+            "the maps between soil, water go in a circle"
+        );
+        assert_eq!(
+            error("seeds: 1\n\nseed-to-location map:\n1 2 3\n5 4 2\n"),
+            "the sources 2..5 and 4..6 of the seed-to-location map overlap"
+        );
+        assert_eq!(
+            error(&format!(
+                "seeds: 1\n\nseed-to-soil map:\n\nsoil-to-location map:\n\n{}",
+                seed_to_location
+            )),
+            "seed has two maps, seed-to-soil and seed-to-location"
+        );
+        assert_eq!(
+            error(&format!(
+                "seeds: 1\n\n{}\nsoil-to-location map:\n",
+                seed_to_location
+            )),
+            "location has two maps to it, seed-to-location and soil-to-location"
+        );
+        assert!(error("seeds: 1\n\nseed to location map:\n").contains("expected a header"));
+    }
+
+    #[test]
+    fn empty_mappings_map_nothing() {
+        for input in [
+            "seeds: 3 5\n\nseed-to-location map:\n0 5 0\n",
+            "seeds: 3 5\n\nseed-to-location map:\n0 5 0\n9 5 2\n",
+            "seeds: 3 5\n\nseed-to-location map:\n9 5 2\n0 5 0\n",
+        ] {
+            let mut day05 = crate::day05::Day05::default();
+            day05.parse_input(input).unwrap();
+            let expected = day05.reference_answers(input);
+            assert_eq!(expected, (Some("3".into()), Some("3".into())));
+            assert_eq!((day05.part_1().unwrap(), day05.part_2().unwrap()), expected);
+            day05.select_variant("reverse").unwrap();
+            assert_eq!(day05.part_2().unwrap(), expected.1, "{}", input);
+        }
+    }
+
+    #[test]
+    fn going_back_through_each_map() {
+        use crate::rng::Rng;
+
+        for seed in 0..20 {
+            let input = super::generator::generate(&mut Rng::new(seed), 5);
+            let mut day05 = crate::day05::Day05::default();
+            day05.parse_input(&input).unwrap();
+
+            let locations = 100..200;
+            let stepwise = day05
+                .maps
+                .iter()
+                .rev()
+                .fold(vec![locations.clone()], |ranges, map| {
+                    let map = map.piecewise();
+                    let mut back = ranges
+                        .into_iter()
+                        .flat_map(|r| map.preimage(r))
+                        .collect::<Vec<_>>();
+                    back.sort_unstable_by_key(|r| r.start);
+                    back
+                });
+            let flatten = |ranges: Vec<std::ops::Range<u64>>| {
+                let mut seeds = ranges.into_iter().flatten().collect::<Vec<_>>();
+                seeds.sort_unstable();
+                seeds
+            };
+            assert_eq!(
+                flatten(day05.seeds_landing_in(locations)),
+                flatten(stepwise),
+                "{}",
+                input
+            );
+        }
     }
 }
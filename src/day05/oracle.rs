@@ -0,0 +1,47 @@
+//! Reference answers that push each seed, one at a time, through every line of every map.
+
+/// Walks one seed through every map by scanning all of its lines.
+fn location(maps: &[Vec<Vec<u64>>], seed: u64) -> u64 {
+    let mut value = seed;
+    for map in maps {
+        if let Some(line) = map.iter().find(|l| value >= l[1] && value < l[1] + l[2]) {
+            value = line[0] + value - line[1];
+        }
+    }
+    value
+}
+
+fn almanac(input: &str) -> (Vec<u64>, Vec<Vec<Vec<u64>>>) {
+    let mut sections = input.split("\n\n");
+    let seeds = sections.next().unwrap()["seeds:".len()..]
+        .split_whitespace()
+        .map(|n| n.parse().unwrap())
+        .collect();
+    let maps = sections
+        .map(|s| {
+            s.lines()
+                .skip(1)
+                .map(|l| l.split_whitespace().map(|n| n.parse().unwrap()).collect())
+                .collect()
+        })
+        .collect();
+    (seeds, maps)
+}
+
+pub fn part_1(input: &str) -> u64 {
+    let (seeds, maps) = almanac(input);
+    seeds.iter().map(|&s| location(&maps, s)).min().unwrap()
+}
+
+/// Tries every seed of every range, `None` when the seeds do not pair up.
+pub fn part_2(input: &str) -> Option<u64> {
+    let (seeds, maps) = almanac(input);
+    if !seeds.len().is_multiple_of(2) {
+        return None;
+    }
+    seeds
+        .chunks(2)
+        .flat_map(|pair| pair[0]..pair[0] + pair[1])
+        .map(|s| location(&maps, s))
+        .min()
+}
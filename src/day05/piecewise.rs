@@ -0,0 +1,318 @@
+//! Maps from `u64` to `u64` that are a shift on each of a sorted list of ranges, which is what
+//! every almanac map is and what any chain of them composes into. Pieces can land on top of
+//! each other, so going back from a value can give any number of sources.
+
+use std::fmt::{self, Display};
+use std::ops::Range;
+
+/// Everything from `start` up to where the next piece starts goes to `target` onwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Piece {
+    start: u64,
+    target: u64,
+}
+
+/// Always starts with a piece at 0, and the last piece is the identity, so every `u64` has a
+/// value and shifting one never overflows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Piecewise {
+    pieces: Vec<Piece>,
+}
+
+impl Default for Piecewise {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Piecewise {
+    pub fn identity() -> Self {
+        Self {
+            pieces: vec![Piece {
+                start: 0,
+                target: 0,
+            }],
+        }
+    }
+
+    /// Sends each source range to the range starting at its target, everything else to itself.
+    /// Where sources overlap the one starting first wins. Empty sources send nothing anywhere
+    /// and are left out.
+    pub fn from_ranges(ranges: impl IntoIterator<Item = (Range<u64>, u64)>) -> Self {
+        let mut ranges = ranges
+            .into_iter()
+            .filter(|(source, _)| !source.is_empty())
+            .collect::<Vec<_>>();
+        ranges.sort_unstable_by_key(|(source, _)| source.start);
+
+        let mut pieces = Vec::with_capacity(2 * ranges.len() + 1);
+        // Everything below `covered` has a piece already.
+        let mut covered = 0;
+        for (source, target) in ranges {
+            if source.end <= covered {
+                continue;
+            }
+            let start = source.start.max(covered);
+            if covered < start {
+                pieces.push(Piece {
+                    start: covered,
+                    target: covered,
+                });
+            }
+            pieces.push(Piece {
+                start,
+                target: target + (start - source.start),
+            });
+            covered = source.end;
+        }
+        pieces.push(Piece {
+            start: covered,
+            target: covered,
+        });
+
+        Self::normalized(pieces)
+    }
+
+    /// Joins pieces that continue where the previous one left off.
+    fn normalized(pieces: Vec<Piece>) -> Self {
+        let mut joined: Vec<Piece> = Vec::with_capacity(pieces.len());
+        for piece in pieces {
+            match joined.last() {
+                Some(last)
+                    if last.target.checked_add(piece.start - last.start) == Some(piece.target) => {}
+                _ => joined.push(piece),
+            }
+        }
+        Self { pieces: joined }
+    }
+
+    /// Index of the piece `x` is in.
+    fn piece_of(&self, x: u64) -> usize {
+        self.pieces.partition_point(|p| p.start <= x) - 1
+    }
+
+    /// Where the piece at `i` ends, `None` for the last one.
+    fn end_of(&self, i: usize) -> Option<u64> {
+        self.pieces.get(i + 1).map(|p| p.start)
+    }
+
+    pub fn get(&self, x: u64) -> u64 {
+        let piece = self.pieces[self.piece_of(x)];
+        piece.target + (x - piece.start)
+    }
+
+    /// Where a new piece starts, in increasing order. Between two breakpoints the map is `x`
+    /// plus a constant.
+    pub fn breakpoints(&self) -> impl Iterator<Item = u64> + '_ {
+        self.pieces.iter().skip(1).map(|p| p.start)
+    }
+
+    /// Every piece as its source range and where that starts to go, in order of the sources.
+    /// The last piece is open ended and its range stops short of `u64::MAX`.
+    pub fn pieces(&self) -> impl Iterator<Item = (Range<u64>, u64)> + '_ {
+        self.pieces.iter().enumerate().map(|(i, piece)| {
+            let end = self.end_of(i).unwrap_or(u64::MAX);
+            (piece.start..end, piece.target)
+        })
+    }
+
+    /// Every `x` that goes to `y`, in increasing order.
+    pub fn sources(&self, y: u64) -> Vec<u64> {
+        self.pieces
+            .iter()
+            .enumerate()
+            .filter(|(i, p)| {
+                y >= p.target
+                    && self
+                        .end_of(*i)
+                        .is_none_or(|end| y - p.target < end - p.start)
+            })
+            .map(|(_, p)| p.start + (y - p.target))
+            .collect()
+    }
+
+    /// Every `x` that goes somewhere in `range`, as increasing ranges with gaps between them.
+    pub fn preimage(&self, range: Range<u64>) -> Vec<Range<u64>> {
+        let mut preimage: Vec<Range<u64>> = Vec::new();
+        // Each piece's sources come after the previous one's, so this is sorted already.
+        for (source, target) in self.pieces() {
+            let image_end = target + (source.end - source.start);
+            let (low, high) = (range.start.max(target), range.end.min(image_end));
+            if low >= high {
+                continue;
+            }
+            let found = source.start + (low - target)..source.start + (high - target);
+            match preimage.last_mut() {
+                Some(last) if last.end == found.start => last.end = found.end,
+                _ => preimage.push(found),
+            }
+        }
+        preimage
+    }
+
+    /// `self` followed by `next`.
+    pub fn then(&self, next: &Self) -> Self {
+        let mut pieces = Vec::with_capacity(self.pieces.len() + next.pieces.len());
+        for (i, piece) in self.pieces.iter().enumerate() {
+            let image_end = self.end_of(i).map(|end| piece.target + (end - piece.start));
+            // Walk through the pieces of `next` the image of this piece covers.
+            let mut at = piece.target;
+            let mut j = next.piece_of(at);
+            loop {
+                let outer = next.pieces[j];
+                pieces.push(Piece {
+                    start: piece.start + (at - piece.target),
+                    target: outer.target + (at - outer.start),
+                });
+                match (next.end_of(j), image_end) {
+                    (Some(end), Some(image_end)) if end < image_end => at = end,
+                    (Some(end), None) => at = end,
+                    _ => break,
+                }
+                j += 1;
+            }
+        }
+
+        Self::normalized(pieces)
+    }
+
+    /// The smallest value of any `x` in `range`. Each piece is increasing, so only the first
+    /// `x` of every piece the range touches needs to be looked at.
+    pub fn lowest_in(&self, range: Range<u64>) -> Option<u64> {
+        if range.is_empty() {
+            return None;
+        }
+        self.pieces[self.piece_of(range.start)..]
+            .iter()
+            .take_while(|p| p.start < range.end)
+            .map(|p| {
+                let x = p.start.max(range.start);
+                p.target + (x - p.start)
+            })
+            .min()
+    }
+}
+
+/// One piece per line, `start..end -> target..target_end`, the last one open ended.
+impl Display for Piecewise {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, piece) in self.pieces.iter().enumerate() {
+            match self.end_of(i) {
+                Some(end) => writeln!(
+                    f,
+                    "{}..{} -> {}..{}",
+                    piece.start,
+                    end,
+                    piece.target,
+                    piece.target + (end - piece.start)
+                )?,
+                None => writeln!(f, "{}.. -> {}..", piece.start, piece.target)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::rng::Rng;
+
+    use super::*;
+
+    fn seed_to_soil() -> Piecewise {
+        Piecewise::from_ranges([(98..100, 50), (50..98, 52)])
+    }
+
+    #[test]
+    fn one_map() {
+        let map = seed_to_soil();
+        assert_eq!(map.breakpoints().collect::<Vec<_>>(), [50, 98, 100]);
+        assert_eq!(
+            [0, 49, 50, 79, 97, 98, 99, 100, u64::MAX].map(|x| map.get(x)),
+            [0, 49, 52, 81, 99, 50, 51, 100, u64::MAX]
+        );
+        assert_eq!(
+            map.to_string(),
+            "0..50 -> 0..50\n50..98 -> 52..100\n98..100 -> 50..52\n100.. -> 100..\n"
+        );
+        assert_eq!(map.lowest_in(60..99), Some(50));
+        assert_eq!(map.lowest_in(60..60), None);
+    }
+
+    #[test]
+    fn identity_pieces_are_joined() {
+        assert_eq!(Piecewise::from_ranges([(5..10, 5)]), Piecewise::identity());
+        assert_eq!(Piecewise::from_ranges([(5..5, 0)]), Piecewise::identity());
+        assert_eq!(
+            Piecewise::from_ranges([(5..5, 0), (5..8, 20)]),
+            Piecewise::from_ranges([(5..8, 20)])
+        );
+        assert_eq!(
+            Piecewise::from_ranges([(0..5, 10), (5..10, 15)]),
+            Piecewise::from_ranges([(0..10, 10)])
+        );
+        assert_eq!(
+            Piecewise::from_ranges([(u64::MAX - 1..u64::MAX, 0)]).get(u64::MAX),
+            u64::MAX
+        );
+    }
+
+    #[test]
+    fn going_back() {
+        let map = seed_to_soil();
+        assert_eq!(map.sources(50), [98]);
+        assert_eq!(map.sources(99), [97]);
+        assert_eq!(Piecewise::from_ranges([(0..10, 20)]).sources(25), [5, 25]);
+        assert_eq!(map.sources(u64::MAX), [u64::MAX]);
+        assert_eq!(map.preimage(49..53), [49..51, 98..100]);
+        assert_eq!(map.preimage(0..0), []);
+
+        let mut rng = Rng::new(9);
+        for _ in 0..100 {
+            let map = Piecewise::from_ranges((0..rng.below(6)).map(|_| {
+                let start = rng.below(100);
+                (start..start + rng.range_inclusive(1..=30), rng.below(100))
+            }));
+            let start = rng.below(150);
+            let range = start..start + rng.below(50);
+            let expected = (0..300)
+                .filter(|&x| range.contains(&map.get(x)))
+                .collect::<Vec<_>>();
+            let found = map
+                .preimage(range.clone())
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+            assert_eq!(found, expected, "{:?}\n{}", range, map);
+            for y in range {
+                assert!(map.sources(y).iter().all(|&x| map.get(x) == y));
+            }
+        }
+    }
+
+    #[test]
+    fn composing_matches_applying_in_turn() {
+        let mut rng = Rng::new(5);
+        for _ in 0..100 {
+            let maps = (0..3)
+                .map(|_| {
+                    Piecewise::from_ranges((0..rng.below(5)).map(|_| {
+                        let start = rng.below(100);
+                        (start..start + rng.range_inclusive(1..=30), rng.below(100))
+                    }))
+                })
+                .collect::<Vec<_>>();
+            let composed = maps
+                .iter()
+                .fold(Piecewise::identity(), |acc, m| acc.then(m));
+            for x in 0..200 {
+                let expected = maps.iter().fold(x, |x, m| m.get(x));
+                assert_eq!(composed.get(x), expected, "{}\n{:?}", x, maps);
+            }
+            let lowest = (20..80).map(|x| composed.get(x)).min();
+            assert_eq!(composed.lowest_in(20..80), lowest);
+        }
+    }
+}
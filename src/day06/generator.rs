@@ -0,0 +1,54 @@
+use crate::rng::Rng;
+
+/// Most digits the times can have between them. The records have up to twice as many, and
+/// the concatenated record of part 2 has to fit a `u128`.
+const MAX_DIGITS: usize = 19;
+
+/// Races whose times have `size + 1` digits between them, up to 19, so the number
+/// of races and how long they are both grow with `size`. The smallest sizes keep the
+/// concatenated race of part 2 brute-forceable. Records are often chosen to equal some hold
+/// time's distance exactly, which puts the quadratic's roots on integers, and sometimes
+/// cannot be beaten at all.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let digits = (size + 1).min(MAX_DIGITS);
+    // About as many races as each of them has digits.
+    let root = digits.isqrt();
+    let per_race = if root * root == digits {
+        root
+    } else {
+        root + 1
+    };
+
+    let mut times = Vec::new();
+    let mut distances = Vec::new();
+    let mut left = digits;
+    while left > 0 {
+        let race_digits = per_race.min(left) as u32;
+        left -= race_digits as usize;
+        let time = rng.range_inclusive(10_u64.pow(race_digits - 1)..=10_u64.pow(race_digits) - 1);
+        let best = (time / 2) * (time - time / 2);
+        let distance = match rng.below(3) {
+            0 => {
+                let hold = rng.range_inclusive(0..=time);
+                hold * (time - hold)
+            }
+            1 => rng.range_inclusive(best..=best + 2),
+            _ => rng.below(best + 1),
+        };
+        times.push(time.to_string());
+        distances.push(distance.to_string());
+    }
+
+    let width = distances.iter().map(String::len).max().unwrap_or(0) + 1;
+    let column = |values: &[String]| {
+        values
+            .iter()
+            .map(|v| format!("{:>width$}", v, width = width))
+            .collect::<String>()
+    };
+    format!(
+        "Time:    {}\nDistance:{}\n",
+        column(&times),
+        column(&distances)
+    )
+}
@@ -1,102 +1,308 @@
-use crate::AoCProblem;
-use itertools::Itertools;
+use std::fmt::{self, Display};
+use std::ops::RangeInclusive;
+
+use crate::arith::CheckedIterator;
+use crate::inspect::Stats;
+use crate::parsing::{digit_words, labelled, parse_all};
+use crate::reduce::{self, Chunk};
+use crate::rng::Rng;
+use crate::{unknown_variant, AoCProblem};
+use color_eyre::eyre::{eyre, Result};
+use nom::character::complete::line_ending;
+use nom::sequence::separated_pair;
+
+pub mod generator;
+pub mod oracle;
 
 #[derive(Default)]
 pub struct Day06 {
     data1: Vec<BoatRace>,
     data2: BoatRace,
+    variant: Variant,
+}
+
+#[derive(Default, Clone, Copy)]
+enum Variant {
+    /// Solves the quadratic for the first winning hold.
+    #[default]
+    ClosedForm,
+    /// Tries every hold time.
+    BruteForce,
+}
+
+impl Day06 {
+    /// The races of part 1.
+    pub fn races(&self) -> &[BoatRace] {
+        &self.data1
+    }
+
+    /// The one long race of part 2.
+    pub const fn concatenated_race(&self) -> &BoatRace {
+        &self.data2
+    }
 }
 
 impl AoCProblem for Day06 {
-    fn parse_input(&mut self, input: &str) {
-        let lines = input.lines().collect_vec();
-
-        let mut l1 = lines.iter();
-        let times = l1
-            .next()
-            .unwrap()
-            .split_whitespace()
-            .skip(1)
-            .filter(|x| !x.is_empty())
-            .map(|x| x.trim().parse::<u64>().unwrap());
-        let distances = l1
-            .next()
-            .unwrap()
-            .split_whitespace()
-            .skip(1)
-            .filter(|x| !x.is_empty())
-            .map(|x| x.trim().parse::<u64>().unwrap());
+    fn parse_input(&mut self, input: &str) -> Result<()> {
+        let (times, distances) = parse_all(
+            input,
+            separated_pair(
+                labelled("Time:", digit_words),
+                line_ending,
+                labelled("Distance:", digit_words),
+            ),
+        )?;
+        if times.len() != distances.len() {
+            return Err(eyre!(
+                "got {} times but {} distances",
+                times.len(),
+                distances.len()
+            ));
+        }
 
+        let parse = |n: &str| n.parse::<u128>();
         self.data1 = times
-            .zip(distances)
-            .map(|(t, d)| BoatRace {
-                time: t,
-                distance: d,
+            .iter()
+            .zip(&distances)
+            .map(|(t, d)| {
+                Ok(BoatRace {
+                    time: parse(t)?,
+                    distance: parse(d)?,
+                })
             })
-            .collect_vec();
-
-        let mut l2 = lines.iter();
-        let time = l2
-            .next()
-            .unwrap()
-            .strip_prefix("Time: ")
-            .unwrap()
-            .replace(' ', "")
-            .parse::<u64>()
-            .unwrap();
-        let distance = l2
-            .next()
-            .unwrap()
-            .strip_prefix("Distance: ")
-            .unwrap()
-            .replace(' ', "")
-            .parse::<u64>()
-            .unwrap();
-        self.data2 = BoatRace { time, distance };
-    }
-
-    fn parse_input_default(&mut self) {
-        self.parse_input(include_str!("../../inputs/day06.txt"));
-    }
-
-    fn part_1(&self) -> Option<String> {
-        Some(
-            self.data1
-                .iter()
-                .map(|br| br.get_number_of_winning())
-                .product::<u64>()
-                .to_string(),
-        )
+            .collect::<Result<_>>()?;
+
+        self.data2 = BoatRace {
+            time: parse(&times.concat())?,
+            distance: parse(&distances.concat())?,
+        };
+        Ok(())
+    }
+
+    fn puzzle_input(&self) -> &'static str {
+        include_str!("../../inputs/day06.txt")
     }
 
-    fn part_2(&self) -> Option<String> {
-        Some(self.data2.get_number_of_winning().to_string())
+    fn part_1(&self) -> Result<Option<String>> {
+        let product = self
+            .data1
+            .iter()
+            .map(|br| br.winning_holds(self.variant))
+            .checked_product()?;
+
+        Ok(Some(product.to_string()))
+    }
+
+    fn part_2(&self) -> Result<Option<String>> {
+        Ok(Some(self.data2.winning_holds(self.variant).to_string()))
     }
 
     fn get_day_name(&self) -> String {
         "Day 06: Wait For It".into()
     }
+
+    fn solver_version(&self) -> &'static str {
+        "2"
+    }
+
+    fn inspect(&self, stats: &mut Stats) {
+        stats.fact("races", self.data1.len());
+        stats.range("time", self.data1.iter().map(|r| r.time));
+        stats.range("record", self.data1.iter().map(|r| r.distance));
+        stats.fact("concatenated time", self.data2.time);
+        stats.fact("concatenated record", self.data2.distance);
+        let best = self.data2.time / 2;
+        match best.checked_mul(self.data2.time - best) {
+            Some(distance) => stats.fact("longest concatenated distance", distance),
+            None => stats.fact("longest concatenated distance", "more than a u128 holds"),
+        }
+        let product = self
+            .data1
+            .iter()
+            .try_fold(1_u128, |acc, r| acc.checked_mul(r.get_number_of_winning()));
+        match product {
+            Some(product) => stats.fact("product of ways to win", product),
+            None => stats.overflow("the product of ways to win is more than a u128 holds".into()),
+        }
+    }
+
+    fn generate_input(&self, rng: &mut Rng, size: usize) -> String {
+        generator::generate(rng, size)
+    }
+
+    fn variants(&self) -> &'static [&'static str] {
+        &["closed-form", "brute-force"]
+    }
+
+    fn select_variant(&mut self, name: &str) -> Result<()> {
+        self.variant = match name {
+            "closed-form" => Variant::ClosedForm,
+            "brute-force" => Variant::BruteForce,
+            _ => return Err(unknown_variant(self, name)),
+        };
+        Ok(())
+    }
+
+    fn reference_answers(&self, input: &str) -> (Option<String>, Option<String>) {
+        (
+            Some(oracle::part_1(input).to_string()),
+            Some(oracle::part_2(input).to_string()),
+        )
+    }
+
+    fn reduction_chunks<'a>(&self, input: &'a str) -> Vec<Chunk<'a>> {
+        // Both lines are needed, there is nothing to drop.
+        reduce::lines_where(input, |_| false)
+    }
 }
 
-#[derive(Default)]
-struct BoatRace {
-    time: u64,
-    distance: u64,
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BoatRace {
+    pub time: u128,
+    /// The record to beat.
+    pub distance: u128,
+}
+
+/// Everything about how a race can be won.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RaceAnalysis {
+    pub race: BoatRace,
+    /// The holds that beat the record, `None` when none does.
+    pub winning: Option<RangeInclusive<u128>>,
+    /// Half the race rounded down, holding for the other half goes just as far.
+    pub best_hold: u128,
+    /// How far the best hold goes, `None` if that is more than a `u128` holds.
+    pub best_distance: Option<u128>,
 }
 
 impl BoatRace {
-    fn get_number_of_winning(&self) -> u64 {
-        let determinant = ((self.time * self.time - 4 * self.distance) as f64).sqrt();
-        let x1 = ((-(self.time as f64) + determinant) / -2.0 + 0.0005).ceil() as u64;
-        let x2 = ((-(self.time as f64) - determinant) / -2.0 - 0.0005).floor() as u64;
+    /// How far holding for `hold` goes, `None` if that is more than a `u128` holds.
+    pub fn distance_for(&self, hold: u128) -> Option<u128> {
+        hold.checked_mul(self.time.checked_sub(hold)?)
+    }
+
+    /// By how much holding for `hold` beats the record, `None` if it does not or the distance
+    /// is more than a `u128` holds.
+    pub fn margin(&self, hold: u128) -> Option<u128> {
+        self.distance_for(hold)?
+            .checked_sub(self.distance)
+            .filter(|&m| m > 0)
+    }
+
+    pub fn analyse(&self) -> RaceAnalysis {
+        let count = self.get_number_of_winning();
+        let winning = (count > 0).then(|| {
+            let first = (self.time - count).div_ceil(2);
+            first..=self.time - first
+        });
+        let best_hold = self.time / 2;
+
+        RaceAnalysis {
+            race: self.clone(),
+            winning,
+            best_hold,
+            best_distance: self.distance_for(best_hold),
+        }
+    }
+
+    fn winning_holds(&self, variant: Variant) -> u128 {
+        match variant {
+            Variant::ClosedForm => self.get_number_of_winning(),
+            Variant::BruteForce => (0..=self.time).filter(|&hold| self.wins(hold)).count() as u128,
+        }
+    }
+
+    /// Whether holding for `hold` beats the record. A distance too large for a `u128` beats
+    /// any record there can be.
+    fn wins(&self, hold: u128) -> bool {
+        hold.checked_mul(self.time - hold)
+            .is_none_or(|distance| distance > self.distance)
+    }
+
+    fn get_number_of_winning(&self) -> u128 {
+        // The winning holds are symmetric around `time / 2`, which is also the best hold.
+        let best = self.time / 2;
+        if !self.wins(best) {
+            return 0;
+        }
+
+        // The first win is the lower root of `hold * (time - hold) = distance` rounded up, and
+        // beating the record at the best hold means `4 * distance < time * time`.
+        let first = match self.time.checked_mul(self.time) {
+            Some(square) => {
+                let root = (square - 4 * self.distance).isqrt();
+                let mut first = (self.time - root) / 2;
+                // `isqrt` rounds down, so this is at most one or two holds off.
+                while first > 0 && self.wins(first - 1) {
+                    first -= 1;
+                }
+                while !self.wins(first) {
+                    first += 1;
+                }
+                first
+            }
+            // Too large to square, but distances only grow up to the best hold.
+            None => {
+                let (mut low, mut high) = (0, best);
+                while low < high {
+                    let middle = low + (high - low) / 2;
+                    if self.wins(middle) {
+                        high = middle;
+                    } else {
+                        low = middle + 1;
+                    }
+                }
+                low
+            }
+        };
+
+        // Holding for no time at all never wins, so `first` is at least one.
+        self.time - 2 * first + 1
+    }
+}
+
+impl RaceAnalysis {
+    pub fn ways_to_win(&self) -> u128 {
+        self.winning.as_ref().map_or(0, |w| w.end() - w.start() + 1)
+    }
+}
 
-        x2 - x1 + 1
+impl Display for RaceAnalysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |d: Option<u128>| d.map_or_else(|| "beyond u128".to_string(), |d| d.to_string());
+        writeln!(f, "time {}, record {}", self.race.time, self.race.distance)?;
+        writeln!(
+            f,
+            "\tbest hold {} goes {}",
+            self.best_hold,
+            show(self.best_distance)
+        )?;
+        match &self.winning {
+            Some(winning) => {
+                writeln!(
+                    f,
+                    "\twinning holds {}..={} ({} ways)",
+                    winning.start(),
+                    winning.end(),
+                    self.ways_to_win()
+                )?;
+                writeln!(
+                    f,
+                    "\tmargins from {} at either end up to {} at the best hold",
+                    show(self.race.margin(*winning.start())),
+                    show(self.race.margin(self.best_hold))
+                )
+            }
+            None => writeln!(f, "\tno hold beats the record"),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day06::Day06;
+    use pretty_assertions::assert_eq;
+
+    use crate::day06::{BoatRace, Day06};
     use crate::AoCProblem;
 
     #[test]
@@ -105,8 +311,8 @@ mod tests {
 Distance:  9  40  200";
 
         let mut d = Day06::default();
-        d.parse_input(input);
-        assert_eq!(d.part_1(), Some("288".into()))
+        d.parse_input(input).unwrap();
+        assert_eq!(d.part_1().unwrap(), Some("288".into()))
     }
 
     #[test]
@@ -115,7 +321,61 @@ Distance:  9  40  200";
 Distance:  9  40  200";
 
         let mut d = Day06::default();
-        d.parse_input(input);
-        assert_eq!(d.part_2(), Some("71503".into()))
+        d.parse_input(input).unwrap();
+        assert_eq!(d.part_2().unwrap(), Some("71503".into()))
+    }
+
+    #[test]
+    fn analysis() {
+        let input = "Time:      7  15   30
+Distance:  9  40  200";
+
+        let mut d = Day06::default();
+        d.parse_input(input).unwrap();
+        let first = d.races()[0].analyse();
+        assert_eq!(first.winning, Some(2..=5));
+        assert_eq!((first.best_hold, first.best_distance), (3, Some(12)));
+        assert_eq!(first.ways_to_win(), 4);
+        assert_eq!(
+            (1..=6).map(|h| d.races()[0].margin(h)).collect::<Vec<_>>(),
+            [None, Some(1), Some(3), Some(3), Some(1), None]
+        );
+        assert_eq!(
+            first.to_string(),
+            "time 7, record 9
+\tbest hold 3 goes 12
+\twinning holds 2..=5 (4 ways)
+\tmargins from 1 at either end up to 3 at the best hold
+"
+        );
+        assert_eq!(d.concatenated_race().analyse().ways_to_win(), 71503);
+
+        let unbeatable = BoatRace {
+            time: 4,
+            distance: 4,
+        }
+        .analyse();
+        assert_eq!(unbeatable.winning, None);
+        assert!(unbeatable
+            .to_string()
+            .ends_with("no hold beats the record\n"));
+        let huge = BoatRace {
+            time: u128::MAX,
+            distance: 1,
+        };
+        assert_eq!(huge.analyse().best_distance, None);
+        assert_eq!(huge.analyse().winning, Some(1..=u128::MAX - 1));
+    }
+
+    #[test]
+    pub fn brute_force() {
+        let input = "Time:      7  15   30
+Distance:  9  40  200";
+
+        let mut d = Day06::default();
+        d.parse_input(input).unwrap();
+        d.select_variant("brute-force").unwrap();
+        assert_eq!(d.part_1().unwrap(), Some("288".into()));
+        assert_eq!(d.part_2().unwrap(), Some("71503".into()))
     }
 }
@@ -0,0 +1,112 @@
+//! Reference answers that try every possible hold time.
+
+pub fn winning_holds(time: u128, distance: u128) -> u128 {
+    (0..=time).filter(|h| h * (time - h) > distance).count() as u128
+}
+
+fn lines(input: &str) -> Vec<Vec<&str>> {
+    input
+        .lines()
+        .map(|l| l.split_whitespace().skip(1).collect())
+        .collect()
+}
+
+pub fn part_1(input: &str) -> u128 {
+    let lines = lines(input);
+    lines[0]
+        .iter()
+        .zip(&lines[1])
+        .map(|(t, d)| winning_holds(t.parse().unwrap(), d.parse().unwrap()))
+        .product()
+}
+
+pub fn part_2(input: &str) -> u128 {
+    let lines = lines(input);
+    winning_holds(
+        lines[0].concat().parse().unwrap(),
+        lines[1].concat().parse().unwrap(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::super::BoatRace;
+    use crate::rng::Rng;
+
+    #[test]
+    fn closed_form_matches_brute_force() {
+        for time in 0..=60 {
+            for distance in 0..=time * time / 4 + 2 {
+                let race = BoatRace { time, distance };
+                assert_eq!(
+                    race.get_number_of_winning(),
+                    super::winning_holds(time, distance),
+                    "time {} distance {}",
+                    time,
+                    distance
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn closed_form_matches_brute_force_at_random() {
+        let mut rng = Rng::new(6);
+        for _ in 0..2000 {
+            let time = rng.range_inclusive(0..=3000) as u128;
+            let best = (time / 2) * (time - time / 2);
+            // Mostly records right at or next to some hold's distance, where roots are exact.
+            let distance = match rng.below(3) {
+                0 => {
+                    let hold = rng.range_inclusive(0..=time as u64) as u128;
+                    hold * (time - hold) + rng.below(2) as u128
+                }
+                1 => best - rng.below(best.min(3) as u64 + 1) as u128 + rng.below(3) as u128,
+                _ => rng.below(best as u64 + 2) as u128,
+            };
+            let race = BoatRace { time, distance };
+            assert_eq!(
+                race.get_number_of_winning(),
+                super::winning_holds(time, distance),
+                "time {} distance {}",
+                time,
+                distance
+            );
+        }
+    }
+
+    #[test]
+    fn beyond_u64() {
+        let mut rng = Rng::new(7);
+        for _ in 0..1000 {
+            let time = match rng.below(3) {
+                0 => u128::MAX - rng.below(10) as u128,
+                1 => (rng.below(u64::MAX) as u128) << rng.below(64),
+                _ => rng.below(u64::MAX) as u128,
+            };
+            let hold = ((rng.below(u64::MAX) as u128) << 64 | rng.below(u64::MAX) as u128)
+                % (time / 2 + 1);
+            let distance = hold
+                .saturating_mul(time - hold)
+                .saturating_sub(rng.below(2) as u128);
+            let race = BoatRace { time, distance };
+            let count = race.get_number_of_winning();
+            if count == 0 {
+                assert!(!race.wins(time / 2));
+                continue;
+            }
+            // The wins are exactly the holds from `first` to `time - first`.
+            let first = (time - count).div_ceil(2);
+            let last = time - first;
+            assert!(
+                race.wins(first) && !race.wins(first - 1),
+                "time {} distance {}",
+                time,
+                distance
+            );
+            assert!(race.wins(last) && (last == time || !race.wins(last + 1)));
+        }
+    }
+}
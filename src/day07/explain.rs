@@ -0,0 +1,275 @@
+//! Why a hand ranks where it does, for checking by hand what the rules make of it.
+
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+
+use color_eyre::eyre::{eyre, Result};
+
+use crate::arith::OrOverflow;
+
+use super::rules::RuleSet;
+use super::{Day07, GameHand, Hand, HandKind};
+
+/// How a hand plays among the hands of the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    pub cards: String,
+    pub kind: HandKind,
+    /// The wild card and what each of them counts as, in the order they come in, `None` if the
+    /// hand has no wild cards.
+    pub wilds: Option<(char, Vec<char>)>,
+    /// 1 for the weakest hand. A hand that isn't in the input ranks where it would if it was.
+    pub rank: usize,
+    /// How many hands there are, the explained one included.
+    pub hands: usize,
+    /// The bid and the winnings, `None` for a hand that isn't in the input.
+    pub bid: Option<(u32, u64)>,
+}
+
+/// Why `stronger` beats `weaker`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comparison {
+    pub stronger: String,
+    pub weaker: String,
+    pub reason: Reason,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reason {
+    /// The kind of the stronger hand and of the weaker one.
+    Kind(HandKind, HandKind),
+    /// Both are of the same kind, and the card at `position`, counting from 1, is the first
+    /// that differs.
+    Card {
+        kind: HandKind,
+        position: usize,
+        stronger: char,
+        weaker: char,
+    },
+    /// Neither is stronger, the hands are the same.
+    Same,
+}
+
+impl Day07 {
+    /// How `cards` plays by `rules` among the hands of the input.
+    pub fn explain(&self, cards: &str, rules: &RuleSet) -> Result<Explanation> {
+        let hand = self.hand(cards)?;
+        let (ranks, kind) = classify(&hand, rules)?;
+        let key = GameHand::new(&hand, rules)?.key;
+
+        let mut weaker = 0;
+        let mut bid = None;
+        for other in &self.hands {
+            let other_key = GameHand::new(other, rules)?.key;
+            if other_key < key {
+                weaker += 1;
+            } else if other_key == key && bid.is_none() {
+                bid = Some(other.bid);
+            }
+        }
+        let rank = weaker + 1;
+        let bid = match bid {
+            Some(bid) => Some((
+                bid,
+                (rank as u64)
+                    .checked_mul(bid as u64)
+                    .or_overflow("the winnings")?,
+            )),
+            None => None,
+        };
+
+        let wilds_as = rules.wilds_as(&ranks);
+        Ok(Explanation {
+            cards: cards.to_string(),
+            kind,
+            wilds: (!wilds_as.is_empty()).then(|| {
+                let played = wilds_as.into_iter().map(|rank| rules.card(rank)).collect();
+                (rules.wild().unwrap(), played)
+            }),
+            rank,
+            hands: self.hands.len() + usize::from(bid.is_none()),
+            bid,
+        })
+    }
+
+    /// Which of two hands is stronger by `rules`, and why.
+    pub fn compare(&self, a: &str, b: &str, rules: &RuleSet) -> Result<Comparison> {
+        let (a, b) = (self.hand(a)?, self.hand(b)?);
+        if a.cards().len() != b.cards().len() {
+            return Err(eyre!("only hands with as many cards can be compared"));
+        }
+        let (ranks_a, kind_a) = classify(&a, rules)?;
+        let (ranks_b, kind_b) = classify(&b, rules)?;
+        let ordering = GameHand::new(&a, rules)?.cmp(&GameHand::new(&b, rules)?);
+        let (stronger, weaker) = match ordering {
+            Ordering::Less => ((&b, &ranks_b, kind_b), (&a, &ranks_a, kind_a)),
+            _ => ((&a, &ranks_a, kind_a), (&b, &ranks_b, kind_b)),
+        };
+
+        let reason = if ordering == Ordering::Equal {
+            Reason::Same
+        } else if stronger.2 != weaker.2 {
+            Reason::Kind(stronger.2, weaker.2)
+        } else {
+            let position = stronger
+                .1
+                .iter()
+                .zip(weaker.1)
+                .position(|(s, w)| s != w)
+                .unwrap();
+            Reason::Card {
+                kind: stronger.2,
+                position: position + 1,
+                stronger: stronger.0.cards()[position],
+                weaker: weaker.0.cards()[position],
+            }
+        };
+
+        Ok(Comparison {
+            stronger: stronger.0.cards().iter().collect(),
+            weaker: weaker.0.cards().iter().collect(),
+            reason,
+        })
+    }
+
+    /// `cards` as a hand, with as many cards as the hands of the input.
+    fn hand(&self, cards: &str) -> Result<Hand> {
+        let hand: Hand = format!("{} 0", cards.trim()).parse()?;
+        match self.hands.first() {
+            Some(first) if first.cards().len() != hand.cards().len() => Err(eyre!(
+                "the hands of the input have {} cards, {:?} has {}",
+                first.cards().len(),
+                cards,
+                hand.cards().len()
+            )),
+            _ => Ok(hand),
+        }
+    }
+}
+
+fn classify(hand: &Hand, rules: &RuleSet) -> Result<(Vec<u8>, HandKind)> {
+    let ranks = hand
+        .cards()
+        .iter()
+        .map(|&c| rules.rank(c))
+        .collect::<Result<Vec<_>>>()?;
+    let kind = rules.kind(&ranks);
+    Ok((ranks, kind))
+}
+
+impl Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.cards, self.kind)?;
+        match &self.wilds {
+            Some((wild, played)) if played.iter().all(|&card| card == played[0]) => {
+                writeln!(f, ", {} counts as {}", wild, played[0])?
+            }
+            Some((wild, played)) => {
+                let played = played.iter().map(char::to_string).collect::<Vec<_>>();
+                writeln!(f, ", {} counts as {} in turn", wild, played.join(" then "))?
+            }
+            None => writeln!(f)?,
+        }
+        match self.bid {
+            Some((bid, winnings)) => writeln!(
+                f,
+                "\trank {} of {}, bid {} wins {}",
+                self.rank, self.hands, bid, winnings
+            ),
+            None => writeln!(
+                f,
+                "\tnot in the input, it would rank {} of {}",
+                self.rank, self.hands
+            ),
+        }
+    }
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.reason {
+            Reason::Kind(stronger, weaker) => writeln!(
+                f,
+                "{} beats {}: {} beats {}",
+                self.stronger, self.weaker, stronger, weaker
+            ),
+            Reason::Card {
+                kind,
+                position,
+                stronger,
+                weaker,
+            } => writeln!(
+                f,
+                "{} beats {}: both are {}, card {} is {} against {}",
+                self.stronger, self.weaker, kind, position, stronger, weaker
+            ),
+            Reason::Same => writeln!(f, "{} ties with {}", self.stronger, self.weaker),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::AoCProblem;
+
+    use super::*;
+
+    #[test]
+    fn explanations() {
+        let mut day = Day07::default();
+        day.parse_input("32T3K 765\nT55J5 684\nKK677 28\nKTJJT 220\nQQQJA 483")
+            .unwrap();
+
+        let jokers = RuleSet::jokers();
+        assert_eq!(
+            day.explain("KTJJT", &jokers).unwrap().to_string(),
+            "KTJJT: four of a kind, J counts as T\n\trank 5 of 5, bid 220 wins 1100\n"
+        );
+        assert_eq!(
+            day.explain("KTJJT", &RuleSet::standard())
+                .unwrap()
+                .to_string(),
+            "KTJJT: two pair\n\trank 2 of 5, bid 220 wins 440\n"
+        );
+        assert_eq!(
+            day.explain("JJJJJ", &jokers).unwrap().to_string(),
+            "JJJJJ: five of a kind, J counts as A\n\tnot in the input, it would rank 6 of 6\n"
+        );
+        assert!(day.explain("KTJJ", &jokers).is_err());
+
+        let full_house_first = jokers
+            .clone()
+            .ranking_kinds([
+                HandKind::HighCard,
+                HandKind::OnePair,
+                HandKind::TwoPair,
+                HandKind::ThreeOfAKind,
+                HandKind::FourOfAKind,
+                HandKind::FiveOfAKind,
+                HandKind::FullHouse,
+            ])
+            .unwrap();
+        assert_eq!(
+            day.explain("KKJJ2", &full_house_first).unwrap().to_string(),
+            "KKJJ2: full house, J counts as K then 2 in turn\n\tnot in the input, it would rank 6 of 6\n"
+        );
+        assert!(day.explain("KTJJX", &jokers).is_err());
+
+        assert_eq!(
+            day.compare("QQQJA", "KTJJT", &jokers).unwrap().to_string(),
+            "KTJJT beats QQQJA: both are four of a kind, card 1 is K against Q\n"
+        );
+        assert_eq!(
+            day.compare("KTJJT", "QQQJA", &RuleSet::standard())
+                .unwrap()
+                .reason,
+            Reason::Kind(HandKind::ThreeOfAKind, HandKind::TwoPair)
+        );
+        assert_eq!(
+            day.compare("T55J5", "T55J5", &jokers).unwrap().reason,
+            Reason::Same
+        );
+    }
+}
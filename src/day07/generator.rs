@@ -0,0 +1,61 @@
+use std::collections::HashSet;
+
+use crate::rng::Rng;
+
+const CARDS: [char; 13] = [
+    '2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A',
+];
+
+/// `size` distinct hands with bids up to 1000. Cards are drawn from a small random subset of
+/// the deck so pairs, full houses and jokers (including `JJJJJ`) are common. Hands have five
+/// cards like the puzzle's, but there are only 13^5 = 371293 different ones of those, so
+/// larger sizes get hands of as many more cards as it takes to have `size` different hands.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut cards = 5;
+    while CARDS.len().pow(cards) < size {
+        cards += 1;
+    }
+    let all_hands = CARDS.len().pow(cards);
+    if size > all_hands / 2 {
+        return most_hands(rng, size, cards);
+    }
+
+    let mut seen = HashSet::new();
+    let mut out = String::new();
+    while seen.len() < size {
+        let deck_size = rng.range_inclusive(1..=6) as usize;
+        let mut deck = CARDS;
+        rng.shuffle(&mut deck);
+        let deck = if rng.chance(1, 3) {
+            // Make sure jokers turn up in at least a third of the hands.
+            let mut deck = deck[..deck_size].to_vec();
+            deck[0] = 'J';
+            deck
+        } else {
+            deck[..deck_size].to_vec()
+        };
+
+        let hand = (0..cards).map(|_| *rng.choose(&deck)).collect::<String>();
+        if seen.insert(hand.clone()) {
+            out.push_str(&format!("{} {}\n", hand, rng.range_inclusive(1..=1000)));
+        }
+    }
+    out
+}
+
+/// Drawing until `size` distinct hands turn up gets slow once most of the deck's hands are
+/// needed, so large inputs shuffle the full list of hands instead.
+fn most_hands(rng: &mut Rng, size: usize, cards: u32) -> String {
+    let mut hands = (0..CARDS.len().pow(cards)).collect::<Vec<_>>();
+    rng.shuffle(&mut hands);
+
+    let mut out = String::new();
+    for mut hand in hands.into_iter().take(size) {
+        for _ in 0..cards {
+            out.push(CARDS[hand % CARDS.len()]);
+            hand /= CARDS.len();
+        }
+        out.push_str(&format!(" {}\n", rng.range_inclusive(1..=1000)));
+    }
+    out
+}
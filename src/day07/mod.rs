@@ -1,61 +1,271 @@
 use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::{self, Display};
 use std::str::FromStr;
 
-use itertools::Itertools;
+use color_eyre::eyre::{eyre, Result, WrapErr};
+use color_eyre::Report;
+use itertools::{Either, Itertools};
 
+use crate::arith::OrOverflow;
+use crate::inspect::Stats;
+use crate::parallel::prelude::*;
+use crate::parsing::parse_lines;
+use crate::rng::Rng;
+use crate::stream::LineSolver;
 use crate::AoCProblem;
 
+use self::rules::RuleSet;
+
+pub mod explain;
+pub mod generator;
+pub mod oracle;
+pub mod rules;
+
 #[derive(Default, Debug)]
 pub struct Day07 {
-    data: Vec<GameHand>,
-    data2: Vec<GameHand>,
+    hands: Vec<Hand>,
 }
 
 impl AoCProblem for Day07 {
-    fn parse_input(&mut self, input: &str) {
-        self.data = input.lines().map(|l| l.parse().unwrap()).collect_vec();
+    fn parse_input(&mut self, input: &str) -> Result<()> {
+        let hands: Vec<Hand> = parse_lines(input)?;
+        for (i, hand) in hands.iter().enumerate() {
+            if hand.cards().len() != hands[0].cards().len() {
+                return Err(eyre!(
+                    "every hand has {} cards, hand {} has {}",
+                    hands[0].cards().len(),
+                    i + 1,
+                    hand.cards().len()
+                ));
+            }
+        }
+        self.hands = hands;
 
-        self.data2 = input
-            .lines()
-            .map(|l| l.replace('J', "$").parse().unwrap())
-            .collect_vec();
+        Ok(())
     }
 
-    fn parse_input_default(&mut self) {
-        self.parse_input(include_str!("../../inputs/day07.txt"));
+    fn puzzle_input(&self) -> &'static str {
+        include_str!("../../inputs/day07.txt")
     }
 
-    fn part_1(&self) -> Option<String> {
-        let res = self
-            .data
-            .iter()
-            .sorted_unstable()
+    fn part_1(&self) -> Result<Option<String>> {
+        Ok(Some(self.winnings(&RuleSet::standard())?.to_string()))
+    }
+
+    fn part_2(&self) -> Result<Option<String>> {
+        Ok(Some(self.winnings(&RuleSet::jokers())?.to_string()))
+    }
+
+    fn get_day_name(&self) -> String {
+        "Day 07: Camel Cards".into()
+    }
+
+    fn solver_version(&self) -> &'static str {
+        "3"
+    }
+
+    fn inspect(&self, stats: &mut Stats) {
+        stats.fact("hands", self.hands.len());
+        stats.range("bid", self.hands.iter().map(|h| h.bid));
+        stats.fact(
+            "distinct hands",
+            self.hands.iter().map(|h| h.cards()).unique().count(),
+        );
+        for (name, rules) in [
+            ("winnings", RuleSet::standard()),
+            ("winnings with jokers", RuleSet::jokers()),
+        ] {
+            let Ok(hands) = self.deal(&rules) else {
+                continue;
+            };
+            let winnings = hands
+                .iter()
+                .sorted_unstable()
+                .zip(1_u128..)
+                .map(|(h, rank)| h.bid as u128 * rank)
+                .sum::<u128>();
+            stats.fits::<u64, _>(name, winnings);
+        }
+    }
+
+    fn generate_input(&self, rng: &mut Rng, size: usize) -> String {
+        generator::generate(rng, size)
+    }
+
+    fn line_solver(&self) -> Option<Box<dyn LineSolver>> {
+        Some(Box::new(Streaming::new()))
+    }
+
+    fn reference_answers(&self, input: &str) -> (Option<String>, Option<String>) {
+        (
+            Some(oracle::part_1(input).to_string()),
+            Some(oracle::part_2(input).to_string()),
+        )
+    }
+}
+
+impl Day07 {
+    /// The total winnings of every hand played by `rules`.
+    pub fn winnings(&self, rules: &RuleSet) -> Result<u64> {
+        total_winnings(self.deal(rules)?)
+    }
+
+    /// Fails on the first hand with a card `rules` doesn't know.
+    fn deal(&self, rules: &RuleSet) -> Result<Vec<GameHand>> {
+        self.hands
+            .maybe_par_iter()
             .enumerate()
-            .map(|(i, h)| (i + 1) * h.bid as usize)
-            .sum::<usize>();
+            .map(|(i, hand)| GameHand::new(hand, rules).wrap_err_with(|| format!("hand {}", i + 1)))
+            .collect()
+    }
+}
 
-        Some(res.to_string())
+/// A hand that turns up twice has no well defined rank and is an error.
+fn total_winnings(mut hands: Vec<GameHand>) -> Result<u64> {
+    hands.sort_unstable_by_key(|h| h.key);
+    if hands.windows(2).any(|pair| pair[0].key == pair[1].key) {
+        return Err(eyre!("a hand is listed twice, its rank is ambiguous"));
     }
+    hands
+        .iter()
+        .zip(1_u64..)
+        .try_fold(0_u64, |acc, (h, rank)| {
+            rank.checked_mul(h.bid as u64)?.checked_add(acc)
+        })
+        .or_overflow("the total winnings")
+}
+
+/// The most slots a [`Table`] gives every hand, enough for six cards out of a full deck.
+const MAX_SLOTS: usize = 1 << 24;
+
+/// Ranking needs every hand, but there are only so many different hands. Rather than the hands
+/// this keeps a fixed table of the bids by hand, one slot for every hand with the cards read as
+/// a number in base the number of cards, which is all the ranking needs. Hands of too many
+/// cards for that only get slots as they turn up. A hand that turns up twice has no well
+/// defined rank and is an error.
+struct Streaming {
+    part_1: Table,
+    part_2: Table,
+}
+
+/// The size of the table depends on the size of the hands, so it is only made once the first
+/// hand comes along.
+struct Table {
+    rules: RuleSet,
+    size: usize,
+    slots: Option<Slots>,
+}
+
+/// The strength and bid of every hand seen, by slot.
+enum Slots {
+    /// Room for every hand there is.
+    Dense(Vec<Option<(u8, u32)>>),
+    /// Only the hands seen, in no order, for hands of so many cards that there would be more
+    /// than [`MAX_SLOTS`] slots.
+    Sparse(HashMap<u64, (u8, u32)>),
+}
 
-    fn part_2(&self) -> Option<String> {
-        let res = self
-            .data2
+impl Table {
+    fn new(rules: RuleSet) -> Self {
+        Self {
+            rules,
+            size: 0,
+            slots: None,
+        }
+    }
+
+    fn record(&mut self, hand: &Hand) -> Result<()> {
+        let slots = self.slots.get_or_insert_with(|| {
+            self.size = hand.cards().len();
+            u32::try_from(self.size)
+                .ok()
+                .and_then(|size| self.rules.cards().checked_pow(size))
+                .filter(|&slots| slots <= MAX_SLOTS)
+                .map_or_else(
+                    || Slots::Sparse(HashMap::new()),
+                    |slots| Slots::Dense(vec![None; slots]),
+                )
+        });
+        if hand.cards().len() != self.size {
+            return Err(eyre!(
+                "every hand has {} cards, this one has {}",
+                self.size,
+                hand.cards().len()
+            ));
+        }
+
+        let hand = GameHand::new(hand, &self.rules)?;
+        // At most 16 cards to the power of at most 15 cards fits.
+        let slot = hand.ranks(self.size).fold(0, |slot, rank| {
+            slot * self.rules.cards() as u64 + rank as u64
+        });
+        let seen = match slots {
+            Slots::Dense(slots) => slots[slot as usize].replace((hand.strength(), hand.bid)),
+            Slots::Sparse(slots) => slots.insert(slot, (hand.strength(), hand.bid)),
+        };
+        if seen.is_some() {
+            return Err(eyre!("a hand is listed twice, its rank is ambiguous"));
+        }
+        Ok(())
+    }
+
+    fn winnings(&self) -> Result<u64> {
+        // Ranking by the kind and then the cards is ranking by the kind and then the slot.
+        let hands = self
+            .slots
             .iter()
-            .sorted_unstable()
-            .enumerate()
-            .map(|(i, h)| (i + 1) * h.bid as usize)
-            .sum::<usize>();
+            .flat_map(|slots| match slots {
+                Slots::Dense(slots) => {
+                    Either::Left((0..).zip(slots).filter_map(|(slot, hand)| {
+                        hand.map(|(strength, bid)| (strength, slot, bid))
+                    }))
+                }
+                Slots::Sparse(slots) => Either::Right(
+                    slots
+                        .iter()
+                        .map(|(&slot, &(strength, bid))| (strength, slot, bid)),
+                ),
+            })
+            .sorted_unstable();
+        hands
+            .zip(1_u64..)
+            .try_fold(0_u64, |acc, ((_, _, bid), rank)| {
+                rank.checked_mul(bid as u64)?.checked_add(acc)
+            })
+            .or_overflow("the total winnings")
+    }
+}
 
-        Some(res.to_string())
+impl Streaming {
+    fn new() -> Self {
+        Self {
+            part_1: Table::new(RuleSet::standard()),
+            part_2: Table::new(RuleSet::jokers()),
+        }
     }
+}
 
-    fn get_day_name(&self) -> String {
-        "Day 07: Camel Cards".into()
+impl LineSolver for Streaming {
+    fn feed(&mut self, line: &str) -> Result<()> {
+        if line.trim().is_empty() {
+            return Ok(());
+        }
+        let hand = line.parse()?;
+        self.part_1.record(&hand)?;
+        self.part_2.record(&hand)
+    }
+
+    fn finish(self: Box<Self>) -> Result<(Option<String>, Option<String>)> {
+        Ok((
+            Some(self.part_1.winnings()?.to_string()),
+            Some(self.part_2.winnings()?.to_string()),
+        ))
     }
 }
 
 #[derive(Debug, PartialEq, Ord, PartialOrd, Eq, Copy, Clone)]
-enum HandKind {
+pub enum HandKind {
     HighCard,
     OnePair,
     TwoPair,
@@ -65,96 +275,148 @@ enum HandKind {
     FiveOfAKind,
 }
 
-impl From<Vec<CardKind>> for HandKind {
-    fn from(value: Vec<CardKind>) -> Self {
-        use CardKind::*;
+impl HandKind {
+    /// Weakest first, the way the puzzle ranks them.
+    pub const ALL: [Self; 7] = [
+        Self::HighCard,
+        Self::OnePair,
+        Self::TwoPair,
+        Self::ThreeOfAKind,
+        Self::FullHouse,
+        Self::FourOfAKind,
+        Self::FiveOfAKind,
+    ];
 
-        let mut counts = value.iter().counts();
-        let top_card = **counts
-            .iter()
-            .filter(|(&v, _)| *v != Joker)
-            .max_by_key(|(_, &v)| v)
-            .unwrap_or((&&Joker, &5))
-            .0;
-        counts.remove(&Joker);
-
-        let value = value
-            .iter()
-            .map(|&v| if v == Joker { top_card } else { v })
-            .collect_vec();
-        match counts.len() {
-            0 => Self::FiveOfAKind,
-            1 => Self::FiveOfAKind,
-            2 => {
-                let count = value.iter().filter(|&&v| v == value[0]).count();
-                if count == 4 || count == 1 {
-                    Self::FourOfAKind
-                } else {
-                    Self::FullHouse
-                }
-            }
-            3 => {
-                let count = value.iter().filter(|&&v| v == value[0]).count();
-                match count {
-                    3 => Self::ThreeOfAKind,
-                    2 => Self::TwoPair,
-                    1 => {
-                        let count = value.iter().filter(|&&v| v == value[1]).count();
-                        if count == 3 || count == 1 {
-                            Self::ThreeOfAKind
-                        } else {
-                            Self::TwoPair
-                        }
-                    }
-                    _ => unreachable!(),
-                }
-            }
-            4 => Self::OnePair,
-            5 => Self::HighCard,
-            _ => unreachable!(),
+    /// The kind of a hand of `size` cards with `counts` of each different card, most common
+    /// first. A hand of more than five cards is the strongest kind any five of them make, the
+    /// way poker reads seven cards, so six alike and five alike with one more card are both
+    /// five of a kind, while three pairs are two pair. A hand of fewer cards is five of a kind
+    /// when they are all alike.
+    fn from_counts(counts: &[usize], size: usize) -> Self {
+        let most = counts.first().copied().unwrap_or(0);
+        let second = counts.get(1).copied().unwrap_or(0);
+        match (most, second) {
+            (5.., _) => Self::FiveOfAKind,
+            (all, _) if all == size => Self::FiveOfAKind,
+            (4.., _) => Self::FourOfAKind,
+            (3.., 2..) => Self::FullHouse,
+            (3.., _) => Self::ThreeOfAKind,
+            (2, 2) => Self::TwoPair,
+            (2, _) => Self::OnePair,
+            _ => Self::HighCard,
         }
     }
 }
 
-#[derive(Debug, PartialEq, Ord, PartialOrd, Eq, Copy, Clone, Hash)]
-enum CardKind {
-    Joker,
-    Two,
-    Three,
-    Four,
-    Five,
-    Six,
-    Seven,
-    Eight,
-    Nine,
-    Ten,
-    Jack,
-    Queen,
-    King,
-    Ace,
+impl Display for HandKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::HighCard => "high card",
+            Self::OnePair => "one pair",
+            Self::TwoPair => "two pair",
+            Self::ThreeOfAKind => "three of a kind",
+            Self::FullHouse => "full house",
+            Self::FourOfAKind => "four of a kind",
+            Self::FiveOfAKind => "five of a kind",
+        })
+    }
 }
 
-#[derive(Debug)]
+/// The most cards in a hand, as many as fit a [`GameHand`] key.
+const MAX_CARDS: usize = 15;
+
+/// A hand as it is written down, before any rules give its cards a meaning.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Hand {
+    cards: [char; MAX_CARDS],
+    size: u8,
+    bid: u32,
+}
+
+impl Hand {
+    fn cards(&self) -> &[char] {
+        &self.cards[..self.size as usize]
+    }
+}
+
+impl FromStr for Hand {
+    type Err = Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (symbols, bid) = s
+            .trim()
+            .split_once(' ')
+            .ok_or_else(|| eyre!("expected a hand and a bid, got {:?}", s))?;
+        let mut cards = [' '; MAX_CARDS];
+        let mut size = 0;
+        for card in symbols.chars() {
+            *cards.get_mut(size).ok_or_else(|| {
+                eyre!("a hand has at most {} cards, got {:?}", MAX_CARDS, symbols)
+            })? = card;
+            size += 1;
+        }
+        if size == 0 {
+            return Err(eyre!("a hand has no cards"));
+        }
+
+        Ok(Self {
+            cards,
+            size: size as u8,
+            bid: bid
+                .trim()
+                .parse()
+                .wrap_err_with(|| format!("invalid bid {:?}", bid))?,
+        })
+    }
+}
+
+/// A hand played by some rules, ordered by the strength of its kind and then card by card.
+/// All of that is packed into `key`, the strength in the top nibble and the rank of every
+/// card in a nibble below it, the first card highest, so comparing hands compares keys.
+#[derive(Debug, Clone, Copy)]
 struct GameHand {
-    cards: Vec<CardKind>,
+    key: u64,
     bid: u32,
-    kind: HandKind,
+}
+
+impl GameHand {
+    fn new(hand: &Hand, rules: &RuleSet) -> Result<Self> {
+        let mut ranks = [0; MAX_CARDS];
+        for (rank, &card) in ranks.iter_mut().zip(hand.cards()) {
+            *rank = rules.rank(card)?;
+        }
+        let ranks = &ranks[..hand.cards().len()];
+
+        let strength = rules.strength(rules.kind(ranks));
+        let key = ranks
+            .iter()
+            .enumerate()
+            .fold(u64::from(strength) << 60, |key, (i, &rank)| {
+                key | u64::from(rank) << (56 - 4 * i)
+            });
+        Ok(Self { key, bid: hand.bid })
+    }
+
+    /// The ranks of the first `size` cards.
+    fn ranks(&self, size: usize) -> impl Iterator<Item = u8> + '_ {
+        (0..size).map(|i| (self.key >> (56 - 4 * i)) as u8 & 0xF)
+    }
+
+    fn strength(&self) -> u8 {
+        (self.key >> 60) as u8
+    }
 }
 
 impl Eq for GameHand {}
 impl PartialEq for GameHand {
     fn eq(&self, other: &Self) -> bool {
-        self.kind == other.kind && self.cards.eq(&other.cards)
+        self.key == other.key
     }
 }
 
 impl Ord for GameHand {
     fn cmp(&self, other: &Self) -> Ordering {
-        if self.kind == other.kind {
-            self.cards.cmp(&other.cards)
-        } else {
-            self.kind.cmp(&other.kind)
-        }
+        self.key.cmp(&other.key)
     }
 }
 
@@ -164,46 +426,11 @@ impl PartialOrd for GameHand {
     }
 }
 
-impl FromStr for GameHand {
-    type Err = !;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        use CardKind::*;
-
-        let (cards, bid) = s.split_once(' ').unwrap();
-        let cards = cards
-            .chars()
-            .map(|c| match c {
-                'A' => Ace,
-                'K' => King,
-                'Q' => Queen,
-                'J' => Jack,
-                'T' => Ten,
-                '9' => Nine,
-                '8' => Eight,
-                '7' => Seven,
-                '6' => Six,
-                '5' => Five,
-                '4' => Four,
-                '3' => Three,
-                '2' => Two,
-                '$' => Joker,
-                _ => unreachable!(),
-            })
-            .collect_vec();
-        let hand_kind = cards.clone().into();
-
-        Ok(Self {
-            cards,
-            bid: bid.parse().unwrap(),
-            kind: hand_kind,
-        })
-    }
-}
-
 #[cfg(test)]
 mod tests {
-    use crate::day07::HandKind;
+    use crate::day07::rules::RuleSet;
+    use crate::day07::{GameHand, Hand, HandKind, MAX_CARDS};
+    use crate::rng::Rng;
     use crate::AoCProblem;
     use pretty_assertions::assert_eq;
 
@@ -216,8 +443,8 @@ KTJJT 220
 QQQJA 483";
 
         let mut day = super::Day07::default();
-        day.parse_input(input);
-        assert_eq!(day.part_1(), Some("6440".into()));
+        day.parse_input(input).unwrap();
+        assert_eq!(day.part_1().unwrap(), Some("6440".into()));
         assert!(HandKind::FiveOfAKind > HandKind::FourOfAKind);
     }
 
@@ -230,7 +457,187 @@ KTJJT 220
 QQQJA 483";
 
         let mut day = super::Day07::default();
-        day.parse_input(input);
-        assert_eq!(day.part_2(), Some("5905".into()));
+        day.parse_input(input).unwrap();
+        assert_eq!(day.part_2().unwrap(), Some("5905".into()));
+    }
+
+    #[test]
+    fn invalid_hands() {
+        let mut day = super::Day07::default();
+        day.parse_input("32T3K 1\n32T3X 765").unwrap();
+        assert_eq!(
+            format!("{:#}", day.part_1().unwrap_err()),
+            "hand 2: unknown card 'X'"
+        );
+        assert!(day.parse_input("32T3K").is_err());
+        assert!(day.parse_input("32T3KK 765\n32T3K 765").is_err());
+        assert!(day.parse_input(" 765").is_err());
+        assert!(day.parse_input("32T3K -1").is_err());
+    }
+
+    #[test]
+    fn six_cards() {
+        let input = "32T3KA 765
+T55J5J 684
+KK677K 28
+KTJJT2 220
+QQQJAQ 483";
+
+        let mut day = super::Day07::default();
+        day.parse_input(input).unwrap();
+        // One pair, full house twice, two pair and four of a kind. With jokers the two pair
+        // becomes four of a kind and the other two five alike, which is five of a kind.
+        assert_eq!(
+            day.part_1().unwrap(),
+            Some((765 + 220 * 2 + 684 * 3 + 28 * 4 + 483 * 5).to_string())
+        );
+        assert_eq!(
+            day.part_2().unwrap(),
+            Some((765 + 28 * 2 + 220 * 3 + 684 * 4 + 483 * 5).to_string())
+        );
+
+        let solver = day.line_solver().unwrap();
+        let streamed = crate::stream::solve_lines(solver, input.as_bytes()).unwrap();
+        assert_eq!(streamed, (day.part_1().unwrap(), day.part_2().unwrap()));
+    }
+
+    #[test]
+    fn kinds_of_bigger_hands() {
+        use HandKind::*;
+
+        // Every way six cards can be alike, with the strongest five-card kind among them.
+        let six = [
+            (&[6][..], FiveOfAKind),
+            (&[5, 1], FiveOfAKind),
+            (&[4, 2], FourOfAKind),
+            (&[4, 1, 1], FourOfAKind),
+            (&[3, 3], FullHouse),
+            (&[3, 2, 1], FullHouse),
+            (&[3, 1, 1, 1], ThreeOfAKind),
+            (&[2, 2, 2], TwoPair),
+            (&[2, 2, 1, 1], TwoPair),
+            (&[2, 1, 1, 1, 1], OnePair),
+            (&[1, 1, 1, 1, 1, 1], HighCard),
+        ];
+        for (counts, kind) in six {
+            assert_eq!(HandKind::from_counts(counts, 6), kind, "{:?}", counts);
+        }
+        assert_eq!(HandKind::from_counts(&[3, 3, 3, 3, 3], 15), FullHouse);
+        assert_eq!(HandKind::from_counts(&[4], 4), FiveOfAKind);
+        assert_eq!(HandKind::from_counts(&[3, 1], 4), ThreeOfAKind);
+
+        let mut day = super::Day07::default();
+        day.parse_input("AAAAAK 1\n222222 10\nKKKKQQ 100").unwrap();
+        // Both five of a kind, then the first card decides.
+        assert_eq!(day.part_1().unwrap(), Some((100 + 10 * 2 + 3).to_string()));
+    }
+
+    #[test]
+    fn streaming_seven_cards() {
+        // Too many hands of seven cards for a slot each, the table only keeps the ones seen.
+        let input = "32T3KA2 765\nT55J5J5 684\nKK677KQ 28\nKTJJT2T 220\nQQQJAQ2 483\n";
+        let mut day = super::Day07::default();
+        day.parse_input(input).unwrap();
+        let solver = day.line_solver().unwrap();
+        let streamed = crate::stream::solve_lines(solver, input.as_bytes()).unwrap();
+        assert_eq!(streamed, (day.part_1().unwrap(), day.part_2().unwrap()));
+
+        let repeated = format!("{}KK677KQ 1\n", input);
+        let solver = day.line_solver().unwrap();
+        let err = crate::stream::solve_lines(solver, repeated.as_bytes()).unwrap_err();
+        assert_eq!(
+            format!("{:#}", err),
+            "line 6: a hand is listed twice, its rank is ambiguous"
+        );
+    }
+
+    #[test]
+    fn repeated_hands() {
+        let input = "32T3K 765\nKK677 28\n32T3K 1\n";
+        let mut day = super::Day07::default();
+        day.parse_input(input).unwrap();
+        assert!(day.part_1().is_err());
+        assert!(day.part_2().is_err());
+
+        let solver = day.line_solver().unwrap();
+        let err = crate::stream::solve_lines(solver, input.as_bytes()).unwrap_err();
+        assert_eq!(
+            format!("{:#}", err),
+            "line 3: a hand is listed twice, its rank is ambiguous"
+        );
+    }
+
+    #[test]
+    fn house_rules() {
+        let mut day = super::Day07::default();
+        day.parse_input("A2345 1\n2345K 10").unwrap();
+        assert_eq!(day.winnings(&RuleSet::standard()).unwrap(), 10 + 2);
+        let aces_low = RuleSet::new("A23456789TJQK", None).unwrap();
+        assert_eq!(day.winnings(&aces_low).unwrap(), 1 + 20);
+        // Cards the puzzle doesn't have are fine as long as the rules played have them.
+        day.parse_input("Z2345 10\n2345A 1").unwrap();
+        let zed_high = RuleSet::new("23456789TJQKAZ", None).unwrap();
+        assert_eq!(day.winnings(&zed_high).unwrap(), 1 + 10 * 2);
+        assert!(day.winnings(&RuleSet::standard()).is_err());
+
+        day.parse_input("KKQQ2 1\n33345 10\nJJ234 100").unwrap();
+        let twos_wild = RuleSet::new("23456789TJQKA", Some('2')).unwrap();
+        assert_eq!(day.winnings(&twos_wild).unwrap(), 10 + 200 + 3);
+        let full_house_first = RuleSet::standard()
+            .ranking_kinds([
+                HandKind::HighCard,
+                HandKind::OnePair,
+                HandKind::TwoPair,
+                HandKind::ThreeOfAKind,
+                HandKind::FourOfAKind,
+                HandKind::FiveOfAKind,
+                HandKind::FullHouse,
+            ])
+            .unwrap();
+        day.parse_input("KKKQQ 1\nAAAA2 10").unwrap();
+        assert_eq!(day.winnings(&full_house_first).unwrap(), 2 + 10);
+    }
+
+    #[test]
+    fn keys_order_by_kind_then_card_by_card() {
+        let mut rng = Rng::new(7);
+        let symbols = "23456789TJQKA".chars().collect::<Vec<_>>();
+        for rules in [RuleSet::standard(), RuleSet::jokers()] {
+            for _ in 0..50 {
+                let size = rng.range_inclusive(1..=MAX_CARDS as u64) as usize;
+                let deck = &symbols[..rng.range_inclusive(1..=13) as usize];
+                let hands = (0..20)
+                    .map(|bid| {
+                        let cards = (0..size).map(|_| *rng.choose(deck)).collect::<String>();
+                        format!("{} {}", cards, bid).parse::<Hand>().unwrap()
+                    })
+                    .collect::<Vec<_>>();
+                // Compared the way they were before they were packed.
+                let unpacked = |hand: &Hand| {
+                    let ranks = hand
+                        .cards()
+                        .iter()
+                        .map(|&c| rules.rank(c).unwrap())
+                        .collect::<Vec<_>>();
+                    (rules.strength(rules.kind(&ranks)), ranks)
+                };
+                for a in &hands {
+                    let key = GameHand::new(a, &rules).unwrap();
+                    assert_eq!(key.ranks(size).collect::<Vec<_>>(), unpacked(a).1);
+                    for b in &hands {
+                        assert_eq!(
+                            key.cmp(&GameHand::new(b, &rules).unwrap()),
+                            unpacked(a).cmp(&unpacked(b)),
+                            "{:?} {:?}",
+                            a.cards(),
+                            b.cards()
+                        );
+                    }
+                }
+            }
+        }
+
+        assert!("23456789TJQKA23 1".parse::<Hand>().is_ok());
+        assert!("23456789TJQKA234 1".parse::<Hand>().is_err());
     }
 }
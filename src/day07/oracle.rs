@@ -0,0 +1,95 @@
+//! Reference answers that classify hands by their sorted card counts and try every card
+//! for every joker.
+
+use std::cmp::Ordering;
+
+/// 6 for five of a kind down to 0 for high card.
+fn strength(hand: &[char]) -> u8 {
+    let mut counts = hand
+        .iter()
+        .map(|c| hand.iter().filter(|d| *d == c).count())
+        .collect::<Vec<_>>();
+    counts.sort_unstable();
+    counts.reverse();
+    match counts[..] {
+        [5, ..] => 6,
+        [4, ..] => 5,
+        [3, 3, 3, 2, 2] => 4,
+        [3, ..] => 3,
+        [2, 2, 2, 2, 1] => 2,
+        [2, ..] => 1,
+        _ => 0,
+    }
+}
+
+/// Tries every possible card for every joker and keeps the best. The order in which jokers
+/// are replaced doesn't matter, so each one only tries cards from the previous one's onwards.
+fn strength_with_jokers(hand: &[char]) -> u8 {
+    fn best(hand: &mut [char], from: usize) -> u8 {
+        let Some(i) = hand.iter().position(|c| *c == 'J') else {
+            return strength(hand);
+        };
+        let replacements = "23456789TQKA".chars().collect::<Vec<_>>();
+        let mut best_strength = 0;
+        for (r, card) in replacements.iter().enumerate().skip(from) {
+            hand[i] = *card;
+            best_strength = best_strength.max(best(hand, r));
+            hand[i] = 'J';
+        }
+        best_strength
+    }
+
+    best(&mut hand.to_vec(), 0)
+}
+
+fn winnings(input: &str, order: &str, strength: fn(&[char]) -> u8) -> u64 {
+    let mut hands = input
+        .lines()
+        .map(|l| {
+            let (cards, bid) = l.split_once(' ').unwrap();
+            let cards = cards.chars().collect::<Vec<_>>();
+            (strength(&cards), cards, bid.parse::<u64>().unwrap())
+        })
+        .collect::<Vec<_>>();
+    hands.sort_by(|(sa, a, _), (sb, b, _)| {
+        sa.cmp(sb).then_with(|| {
+            a.iter()
+                .zip(b)
+                .map(|(x, y)| order.find(*x).cmp(&order.find(*y)))
+                .find(|o| *o != Ordering::Equal)
+                .unwrap_or(Ordering::Equal)
+        })
+    });
+    hands
+        .iter()
+        .enumerate()
+        .map(|(i, (_, _, bid))| (i as u64 + 1) * bid)
+        .sum()
+}
+
+pub fn part_1(input: &str) -> u64 {
+    winnings(input, "23456789TJQKA", strength)
+}
+
+pub fn part_2(input: &str) -> u64 {
+    winnings(input, "J23456789TQKA", strength_with_jokers)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::AoCProblem;
+
+    #[test]
+    fn five_jokers() {
+        let input = "JJJJJ 1\nAAAA2 10\n22223 100";
+        let mut day = super::super::Day07::default();
+        day.parse_input(input).unwrap();
+        assert_eq!(
+            day.part_2().unwrap(),
+            Some(super::part_2(input).to_string())
+        );
+        assert_eq!(super::part_2(input), 100 + 10 * 2 + 3);
+    }
+}
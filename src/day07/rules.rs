@@ -0,0 +1,355 @@
+//! What the cards of a hand mean: the order they rank in, which of them is wild and the order
+//! hand kinds rank in. The puzzle's two parts are [`RuleSet::standard`] and
+//! [`RuleSet::jokers`], anything else is a house rule.
+
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::mem;
+
+use color_eyre::eyre::{eyre, Result};
+use itertools::Itertools;
+
+use super::HandKind;
+
+const NO_CARD: u8 = u8::MAX;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleSet {
+    /// Every card symbol, weakest first.
+    order: Vec<char>,
+    /// The rank of every ASCII card, [`NO_CARD`] for the rest. Looking cards up in `order`
+    /// takes most of the time of ranking a large input otherwise.
+    ascii: [u8; 128],
+    /// The rank of the wild card.
+    wild: Option<u8>,
+    /// The strength of every kind of hand, by the kind.
+    strengths: [u8; 7],
+}
+
+impl RuleSet {
+    /// Part 1, no wild cards.
+    pub fn standard() -> Self {
+        Self::new("23456789TJQKA", None).unwrap()
+    }
+
+    /// Part 2, `J` is a joker that is wild and the weakest card on its own.
+    pub fn jokers() -> Self {
+        Self::new("J23456789TQKA", Some('J')).unwrap()
+    }
+
+    /// Cards rank in the order of `order`, weakest first, and `wild` has to be one of them.
+    /// Where a wild card ranks on its own is up to `order`, so it may well keep its usual place.
+    pub fn new(order: &str, wild: Option<char>) -> Result<Self> {
+        let order = order.chars().collect_vec();
+        if let Some(card) = order.iter().duplicates().next() {
+            return Err(eyre!("the card {:?} is ranked twice", card));
+        }
+        if order.len() > 16 {
+            return Err(eyre!("there are {} cards, at most 16 fit", order.len()));
+        }
+        let wild = match wild {
+            Some(wild) => Some(
+                order
+                    .iter()
+                    .position(|&c| c == wild)
+                    .ok_or_else(|| eyre!("the wild card {:?} is not one of the cards", wild))?
+                    as u8,
+            ),
+            None => None,
+        };
+
+        let mut ascii = [NO_CARD; 128];
+        for (rank, &card) in order.iter().enumerate() {
+            if card.is_ascii() {
+                ascii[card as usize] = rank as u8;
+            }
+        }
+
+        Ok(Self {
+            order,
+            ascii,
+            wild,
+            strengths: HandKind::ALL.map(|k| k as u8),
+        })
+    }
+
+    /// The same cards with hand kinds ranked by `kinds`, weakest first.
+    pub fn ranking_kinds(self, kinds: [HandKind; 7]) -> Result<Self> {
+        if !HandKind::ALL.iter().all(|k| kinds.contains(k)) {
+            return Err(eyre!("{:?} does not rank every kind of hand", kinds));
+        }
+        let mut strengths = [0; 7];
+        for (strength, kind) in kinds.into_iter().enumerate() {
+            strengths[kind as usize] = strength as u8;
+        }
+        Ok(Self { strengths, ..self })
+    }
+
+    pub fn wild(&self) -> Option<char> {
+        self.wild.map(|w| self.order[w as usize])
+    }
+
+    /// How many cards there are.
+    pub fn cards(&self) -> usize {
+        self.order.len()
+    }
+
+    /// 0 for the weakest card and up from there.
+    pub fn rank(&self, card: char) -> Result<u8> {
+        match self.ascii.get(card as usize) {
+            Some(&NO_CARD) => None,
+            Some(&rank) => Some(rank),
+            None => self.order.iter().position(|&c| c == card).map(|r| r as u8),
+        }
+        .ok_or_else(|| eyre!("unknown card {:?}", card))
+    }
+
+    /// The card of rank `rank`.
+    pub fn card(&self, rank: u8) -> char {
+        self.order[rank as usize]
+    }
+
+    /// The rank every wild card of a hand counts as, in the order they come in, empty if there
+    /// are none.
+    pub fn wilds_as(&self, ranks: &[u8]) -> Vec<u8> {
+        self.play_wilds(ranks).1
+    }
+
+    /// 0 for the weakest kind and up from there.
+    pub fn strength(&self, kind: HandKind) -> u8 {
+        self.strengths[kind as usize]
+    }
+
+    /// The kind of a hand given the ranks of its cards, with the wild cards counting as
+    /// whatever makes the strongest kind.
+    pub fn kind(&self, ranks: &[u8]) -> HandKind {
+        if !self.usual_kinds() {
+            return self.play_wilds(ranks).0;
+        }
+
+        let mut counts = [0; 16];
+        for &rank in ranks {
+            counts[rank as usize] += 1;
+        }
+        let wilds = self.wild.map_or(0, |w| mem::take(&mut counts[w as usize]));
+        // Only the two most common cards make a difference, no need to sort the rest.
+        let (mut most, mut second) = (0, 0);
+        for count in counts {
+            if count > most {
+                (most, second) = (count, most);
+            } else if count > second {
+                second = count;
+            }
+        }
+        HandKind::from_counts(&[most + wilds, second], ranks.len())
+    }
+
+    /// Whether kinds rank the way the puzzle ranks them.
+    fn usual_kinds(&self) -> bool {
+        self.strengths == HandKind::ALL.map(|k| k as u8)
+    }
+
+    /// The kind of a hand and what each of its wild cards counts as. With the kinds ranked
+    /// the usual way joining the most common other card always makes the best hand, the
+    /// strongest of those if there is a tie and the strongest card there is for a hand of
+    /// only wild cards. Other orders might want them elsewhere, a full house ranked above
+    /// four of a kind wants `KKKJ2` to be `KKK22`, so then every way of placing them is tried.
+    fn play_wilds(&self, ranks: &[u8]) -> (HandKind, Vec<u8>) {
+        let mut counts = [0; 16];
+        for &rank in ranks {
+            counts[rank as usize] += 1;
+        }
+        let wild = self.wild.unwrap_or(NO_CARD);
+        let wilds = self.wild.map_or(0, |w| mem::take(&mut counts[w as usize]));
+
+        // Of equal counts the last, so strongest, card wins.
+        let joined = counts[..self.cards()]
+            .iter()
+            .enumerate()
+            .filter(|&(rank, _)| rank != wild as usize)
+            .max_by_key(|&(_, count)| count)
+            .map_or(wild, |(rank, _)| rank as u8);
+        let mut best = counts;
+        if wilds > 0 {
+            best[joined as usize] += wilds;
+        }
+        let mut best = (self.kind_of_counts(best), vec![joined; wilds]);
+        if wilds > 0 && !self.usual_kinds() && joined != wild {
+            let mut seen = HashSet::new();
+            self.place_wilds(&mut counts, wilds, &mut Vec::new(), &mut seen, &mut best);
+        }
+        best
+    }
+
+    /// Tries every way of placing the `left` wild cards still to place, and keeps the placing
+    /// with the strongest kind in `best`. Ranks no card has yet are all the same at this
+    /// point, so only the strongest of them is tried.
+    fn place_wilds(
+        &self,
+        counts: &mut [usize; 16],
+        left: usize,
+        placed: &mut Vec<u8>,
+        seen: &mut HashSet<[usize; 16]>,
+        best: &mut (HandKind, Vec<u8>),
+    ) {
+        let mut sorted = *counts;
+        sorted.sort_unstable();
+        if !seen.insert(sorted) {
+            return;
+        }
+        if left == 0 {
+            let kind = self.kind_of_counts(*counts);
+            if self.strength(kind) > self.strength(best.0) {
+                *best = (kind, placed.clone());
+            }
+            return;
+        }
+
+        let wild = self.wild.unwrap() as usize;
+        let unused = (0..self.cards())
+            .rev()
+            .find(|&r| r != wild && counts[r] == 0);
+        // Most common and strongest first, so ties keep the cards joining would pick.
+        let mut targets = (0..self.cards())
+            .filter(|&r| r != wild && counts[r] > 0)
+            .collect_vec();
+        targets.sort_unstable_by_key(|&r| Reverse((counts[r], r)));
+        targets.extend(unused);
+        for rank in targets {
+            counts[rank] += 1;
+            placed.push(rank as u8);
+            self.place_wilds(counts, left - 1, placed, seen, best);
+            placed.pop();
+            counts[rank] -= 1;
+        }
+    }
+
+    /// The kind of a hand with `counts` of each card, no wild cards among them.
+    fn kind_of_counts(&self, mut counts: [usize; 16]) -> HandKind {
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+        HandKind::from_counts(&counts, counts.iter().sum())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn house_rules() {
+        assert!(RuleSet::new("23A4A", None).is_err());
+        assert!(RuleSet::new("234", Some('J')).is_err());
+        assert!(RuleSet::standard()
+            .ranking_kinds([HandKind::HighCard; 7])
+            .is_err());
+
+        let aces_low = RuleSet::new("A23456789TJQK", None).unwrap();
+        assert_eq!(aces_low.rank('A').unwrap(), 0);
+        assert!(aces_low.rank('$').is_err());
+
+        let deuces_wild = RuleSet::new("23456789TJQKA", Some('2')).unwrap();
+        assert_eq!(deuces_wild.wild(), Some('2'));
+        let kind = |hand: &str| {
+            let ranks = hand.chars().map(|c| deuces_wild.rank(c).unwrap());
+            deuces_wild.kind(&ranks.collect_vec())
+        };
+        assert_eq!(kind("2KK3K"), HandKind::FourOfAKind);
+        assert_eq!(kind("22222"), HandKind::FiveOfAKind);
+        assert_eq!(kind("2K3Q4"), HandKind::OnePair);
+        assert_eq!(kind("2KQQ2K"), HandKind::FourOfAKind);
+        assert_eq!(kind("2K3Q4A"), HandKind::OnePair);
+
+        let wilds_as = |rules: &RuleSet, hand: &str| {
+            let ranks = hand.chars().map(|c| rules.rank(c).unwrap()).collect_vec();
+            let wilds_as = rules.wilds_as(&ranks);
+            wilds_as
+                .into_iter()
+                .map(|r| rules.card(r))
+                .collect::<String>()
+        };
+        assert_eq!(wilds_as(&deuces_wild, "2KK3K"), "K");
+        assert_eq!(wilds_as(&deuces_wild, "2K3Q4A"), "A");
+        assert_eq!(wilds_as(&deuces_wild, "22222"), "AAAAA");
+        assert_eq!(wilds_as(&deuces_wild, "3KK3K"), "");
+        assert_eq!(wilds_as(&RuleSet::jokers(), "T55J5"), "5");
+        assert_eq!(wilds_as(&RuleSet::jokers(), "KTJJT"), "TT");
+        assert_eq!(wilds_as(&RuleSet::standard(), "KTJJT"), "");
+    }
+
+    #[test]
+    fn wilds_make_the_strongest_kind_there_is() {
+        use HandKind::*;
+
+        let full_house_first = RuleSet::jokers()
+            .ranking_kinds([
+                HighCard,
+                OnePair,
+                TwoPair,
+                ThreeOfAKind,
+                FourOfAKind,
+                FiveOfAKind,
+                FullHouse,
+            ])
+            .unwrap();
+        let play = |hand: &str| {
+            let ranks = hand
+                .chars()
+                .map(|c| full_house_first.rank(c).unwrap())
+                .collect_vec();
+            let wilds_as = full_house_first.wilds_as(&ranks);
+            (
+                full_house_first.kind(&ranks),
+                wilds_as
+                    .into_iter()
+                    .map(|r| full_house_first.card(r))
+                    .collect::<String>(),
+            )
+        };
+        assert_eq!(play("KKKJ2"), (FullHouse, "2".into()));
+        assert_eq!(play("KKJJ2"), (FullHouse, "K2".into()));
+        assert_eq!(play("KKKKJ"), (FiveOfAKind, "K".into()));
+        assert_eq!(play("JJJJJ"), (FullHouse, "AAAKK".into()));
+        assert_eq!(play("KQ2JJ"), (ThreeOfAKind, "KK".into()));
+        assert_eq!(play("KQ2J3"), (OnePair, "K".into()));
+
+        // Every way of playing the wild cards, none of them is stronger. Hands only hold a
+        // few of the cards, so the wild cards can still play five other ones.
+        let mut rng = crate::rng::Rng::new(3);
+        let cards = "J258".chars().collect_vec();
+        let plays = "23456789".chars().collect_vec();
+        for _ in 0..200 {
+            let hand = (0..5)
+                .map(|_| cards[rng.below(4) as usize])
+                .collect::<String>();
+            let (kind, _) = play(&hand);
+            let wilds = hand.matches('J').count();
+            let strongest = (0..plays.len().pow(wilds as u32))
+                .map(|mut choice| {
+                    let played = hand
+                        .chars()
+                        .map(|c| match c {
+                            'J' => {
+                                let card = plays[choice % plays.len()];
+                                choice /= plays.len();
+                                card
+                            }
+                            c => c,
+                        })
+                        .collect::<String>();
+                    full_house_first.strength(
+                        RuleSet::standard().kind(
+                            &played
+                                .chars()
+                                .map(|c| RuleSet::standard().rank(c).unwrap())
+                                .collect_vec(),
+                        ),
+                    )
+                })
+                .max()
+                .unwrap();
+            assert_eq!(full_house_first.strength(kind), strongest, "{}", hand);
+        }
+    }
+}
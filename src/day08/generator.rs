@@ -0,0 +1,72 @@
+use crate::rng::Rng;
+
+const LABEL_CHARS: &[u8] = b"BCDEFGHIJKLMNOPQRSTUVWXY0123456789";
+/// How many three character labels there are for the nodes between starts and ends.
+const INNER_LABELS: usize = LABEL_CHARS.len().pow(3);
+
+fn label(mut n: usize, suffix: Option<char>) -> String {
+    let len = if suffix.is_some() { 2 } else { 3 };
+    let mut label = String::new();
+    for _ in 0..len {
+        label.push(LABEL_CHARS[n % LABEL_CHARS.len()] as char);
+        n /= LABEL_CHARS.len();
+    }
+    label.extend(suffix);
+    label
+}
+
+/// A network with `size` ghosts (at most 34) that have the clean cycle structure part 2 relies
+/// on: each ghost's start is `k * path.len()` steps away from its end node, and from the end
+/// node the walk repeats exactly as it did from the start. Ghost zero goes from `AAA` to
+/// `ZZZ` so part 1 is defined too. Unused exits point at random nodes. Past eight the path
+/// keeps growing with `size` until the labels run out, so large sizes give large networks.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let ghosts = size.clamp(1, LABEL_CHARS.len());
+    let longest_path = size.clamp(1, (INNER_LABELS / (3 * ghosts)).max(8));
+    let path = (0..rng.range_inclusive(1..=longest_path as u64))
+        .map(|_| if rng.chance(1, 2) { 'L' } else { 'R' })
+        .collect::<Vec<_>>();
+
+    // Every chain as a list of labels, start first and end last.
+    let mut next_label = 0;
+    let mut chains = Vec::new();
+    for ghost in 0..ghosts {
+        let (start, end) = if ghost == 0 {
+            ("AAA".to_string(), "ZZZ".to_string())
+        } else {
+            (label(ghost, Some('A')), label(ghost, Some('Z')))
+        };
+        let steps = rng.range_inclusive(1..=3) as usize * path.len();
+        let mut chain = vec![start];
+        for _ in 1..steps {
+            chain.push(label(next_label, None));
+            next_label += 1;
+        }
+        chain.push(end);
+        chains.push(chain);
+    }
+    let all = chains.iter().flatten().cloned().collect::<Vec<_>>();
+
+    let mut nodes = Vec::new();
+    for chain in &chains {
+        let steps = chain.len() - 1;
+        for (i, node) in chain.iter().enumerate() {
+            // The end node continues like the start did, i.e. into the chain's second node.
+            let next = if i == steps { &chain[1] } else { &chain[i + 1] };
+            let other = rng.choose(&all);
+            let (left, right) = if path[i % path.len()] == 'L' {
+                (next, other)
+            } else {
+                (other, next)
+            };
+            nodes.push(format!("{} = ({}, {})", node, left, right));
+        }
+    }
+    rng.shuffle(&mut nodes);
+
+    format!(
+        "{}\n\n{}\n",
+        path.iter().collect::<String>(),
+        nodes.join("\n")
+    )
+}
@@ -1,14 +1,25 @@
 use std::collections::HashMap;
 use std::str::FromStr;
 
+use color_eyre::eyre::{eyre, Result};
+use color_eyre::Report;
 use itertools::Itertools;
 use nom::bytes::complete::{tag, take};
 use nom::combinator::map;
 use nom::sequence::{delimited, separated_pair};
-use nom::{Finish, IResult};
-
+use nom::IResult;
+
+use crate::arith::OrOverflow;
+use crate::inspect::Stats;
+use crate::parallel::prelude::*;
+use crate::parsing::{blocks, parse_all, parse_lines};
+use crate::reduce::{self, Chunk};
+use crate::rng::Rng;
 use crate::AoCProblem;
 
+pub mod generator;
+pub mod oracle;
+
 #[derive(Default)]
 pub struct Day08 {
     path: Vec<char>,
@@ -16,67 +27,137 @@ pub struct Day08 {
 }
 
 impl AoCProblem for Day08 {
-    fn parse_input(&mut self, input: &str) {
-        let mut parts = input.split("\n\n");
-        self.path = parts.next().unwrap().chars().collect_vec();
-        self.network = parts.next().unwrap().parse().unwrap();
+    fn parse_input(&mut self, input: &str) -> Result<()> {
+        let [path, network] = blocks(input)[..] else {
+            return Err(eyre!(
+                "expected a path and a network separated by a blank line"
+            ));
+        };
+        if let Some(c) = path.chars().find(|c| !matches!(c, 'L' | 'R')) {
+            return Err(eyre!("unknown direction {:?} in path", c));
+        }
+
+        self.path = path.chars().collect_vec();
+        self.network = network.parse()?;
+        Ok(())
     }
 
-    fn parse_input_default(&mut self) {
-        self.parse_input(include_str!("../../inputs/day08.txt"))
+    fn puzzle_input(&self) -> &'static str {
+        include_str!("../../inputs/day08.txt")
     }
 
-    fn part_1(&self) -> Option<String> {
-        let mut current = self.network.nodes.get("AAA").unwrap();
-        let mut path = self.path.iter().cycle();
-        let mut count = 0;
-        while current.label != "ZZZ" {
-            let dir = path.next().unwrap();
-            match dir {
-                'L' => current = self.network.nodes.get(&current.left).unwrap(),
-                'R' => current = self.network.nodes.get(&current.right).unwrap(),
-                _ => unreachable!(),
-            }
-            count += 1;
-        }
+    fn part_1(&self) -> Result<Option<String>> {
+        let count = self.steps("AAA", |label| label == "ZZZ")?;
 
-        Some(count.to_string())
+        Ok(Some(count.to_string()))
     }
 
-    fn part_2(&self) -> Option<String> {
+    fn part_2(&self) -> Result<Option<String>> {
         let starts = self
             .network
             .nodes
             .keys()
             .filter(|k| k.ends_with('A'))
+            .sorted()
             .collect_vec();
-        let counts = starts.iter().map(|&s| {
-            let mut current = s;
-            let mut path = self.path.iter().cycle();
-            let mut count = 0;
-            while !current.ends_with('Z') {
-                let dir = path.next().unwrap();
-                let node = self.network.nodes.get(current).unwrap();
-                match dir {
-                    'L' => current = &node.left,
-                    'R' => current = &node.right,
-                    _ => unreachable!(),
-                };
-                count += 1;
-            }
-            count
-        });
-
-        let lcm = counts.reduce(lcm).unwrap();
-
-        Some(lcm.to_string())
+        let (first, rest) = starts
+            .split_first()
+            .ok_or_else(|| eyre!("no starting nodes ending in 'A'"))?;
+
+        let first = self.steps(first, |label| label.ends_with('Z'))?;
+        let lcm = rest
+            .maybe_par_iter()
+            .map(|s| self.steps(s, |label| label.ends_with('Z')))
+            .sequential()
+            .try_fold(first, |acc, count| lcm(acc, count?))?;
+
+        Ok(Some(lcm.to_string()))
     }
 
     fn get_day_name(&self) -> String {
         "Day 08: Haunted Wasteland".into()
     }
+
+    fn solver_version(&self) -> &'static str {
+        "1"
+    }
+
+    fn inspect(&self, stats: &mut Stats) {
+        let nodes = &self.network.nodes;
+        stats.fact("instructions", self.path.len());
+        stats.fact("nodes", nodes.len());
+        stats.fact("starts", nodes.keys().filter(|k| k.ends_with('A')).count());
+        stats.fact("goals", nodes.keys().filter(|k| k.ends_with('Z')).count());
+        stats.range(
+            "out-degree",
+            nodes
+                .values()
+                .map(|n| if n.left == n.right { 1 } else { 2 }),
+        );
+        stats.fact(
+            "self loops",
+            nodes
+                .values()
+                .filter(|n| n.left == n.label || n.right == n.label)
+                .count(),
+        );
+        stats.fact(
+            "unknown successors",
+            nodes
+                .values()
+                .flat_map(|n| [&n.left, &n.right])
+                .filter(|s| !nodes.contains_key(*s))
+                .count(),
+        );
+    }
+
+    fn generate_input(&self, rng: &mut Rng, size: usize) -> String {
+        generator::generate(rng, size)
+    }
+
+    fn reference_answers(&self, input: &str) -> (Option<String>, Option<String>) {
+        // The reference walks step by step, give up where it would take longer than we
+        // are willing to wait.
+        (
+            oracle::part_1_within(input, REFERENCE_STEPS).map(|n| n.to_string()),
+            oracle::part_2_within(input, REFERENCE_STEPS).map(|n| n.to_string()),
+        )
+    }
+
+    fn reduction_chunks<'a>(&self, input: &'a str) -> Vec<Chunk<'a>> {
+        reduce::lines_where(input, |l| l.contains(" = "))
+    }
+}
+
+impl Day08 {
+    /// Walks from `start` until `is_goal` holds. Once every (node, path position) pair has been
+    /// visited the walk is in a loop, so that many steps without reaching a goal means there is
+    /// no way out.
+    fn steps(&self, start: &str, is_goal: impl Fn(&str) -> bool) -> Result<u64> {
+        let limit = self.network.nodes.len() as u64 * self.path.len() as u64;
+        let mut current = self.network.get(start)?;
+        let mut path = self.path.iter().cycle();
+        let mut count = 0_u64;
+        while !is_goal(&current.label) {
+            if count > limit {
+                return Err(eyre!("no path from {} reaches the goal", start));
+            }
+            let next = match path.next() {
+                Some('L') => &current.left,
+                Some('R') => &current.right,
+                dir => return Err(eyre!("unknown direction {:?} in path", dir)),
+            };
+            current = self.network.get(next)?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
 }
 
+/// How far the reference solver may walk before it gives up.
+const REFERENCE_STEPS: u64 = 10_000_000;
+
 type NodeLabel = String;
 
 #[derive(Default)]
@@ -84,16 +165,21 @@ struct Network {
     nodes: HashMap<NodeLabel, Node>,
 }
 
+impl Network {
+    fn get(&self, label: &str) -> Result<&Node> {
+        self.nodes
+            .get(label)
+            .ok_or_else(|| eyre!("unknown node {:?}", label))
+    }
+}
+
 impl FromStr for Network {
-    type Err = !;
+    type Err = Report;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let nodes = s
-            .lines()
-            .map(|line| {
-                let node: Node = line.parse().unwrap();
-                (node.label.clone(), node)
-            })
+        let nodes = parse_lines::<Node>(s)?
+            .into_iter()
+            .map(|node| (node.label.clone(), node))
             .collect();
 
         Ok(Self { nodes })
@@ -107,11 +193,10 @@ struct Node {
 }
 
 impl FromStr for Node {
-    type Err = !;
+    type Err = Report;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (_, node) = parse_node(s).finish().unwrap();
-        Ok(node)
+        parse_all(s, parse_node)
     }
 }
 
@@ -123,8 +208,10 @@ fn gcd(a: u64, b: u64) -> u64 {
     }
 }
 
-fn lcm(a: u64, b: u64) -> u64 {
-    (a * b) / gcd(a, b)
+fn lcm(a: u64, b: u64) -> Result<u64> {
+    (a / gcd(a, b))
+        .checked_mul(b)
+        .or_overflow(format!("lcm({}, {})", a, b))
 }
 
 fn parse_node_label(i: &str) -> IResult<&str, &str> {
@@ -168,8 +255,8 @@ EEE = (EEE, EEE)
 GGG = (GGG, GGG)
 ZZZ = (ZZZ, ZZZ)";
         let mut problem = super::Day08::default();
-        problem.parse_input(input);
-        assert_eq!(problem.part_1(), Some("2".into()));
+        problem.parse_input(input).unwrap();
+        assert_eq!(problem.part_1().unwrap(), Some("2".into()));
     }
 
     #[test]
@@ -180,8 +267,8 @@ AAA = (BBB, BBB)
 BBB = (AAA, ZZZ)
 ZZZ = (ZZZ, ZZZ)";
         let mut problem = super::Day08::default();
-        problem.parse_input(input);
-        assert_eq!(problem.part_1(), Some("6".into()));
+        problem.parse_input(input).unwrap();
+        assert_eq!(problem.part_1().unwrap(), Some("6".into()));
     }
 
     #[test]
@@ -197,7 +284,25 @@ ZZZ = (ZZZ, ZZZ)";
 22Z = (22B, 22B)
 XXX = (XXX, XXX)";
         let mut problem = super::Day08::default();
-        problem.parse_input(input);
-        assert_eq!(problem.part_2(), Some("6".into()));
+        problem.parse_input(input).unwrap();
+        assert_eq!(problem.part_2().unwrap(), Some("6".into()));
+    }
+
+    #[test]
+    fn unreachable_goal() {
+        let mut problem = super::Day08::default();
+        problem.parse_input("L\n\nBBB = (BBB, BBB)").unwrap();
+        assert!(problem.part_1().is_err());
+        assert!(problem.part_2().is_err());
+
+        problem
+            .parse_input("LR\n\nAAA = (BBB, AAA)\nBBB = (AAA, ZZZ)")
+            .unwrap();
+        assert!(problem.part_1().is_err());
+
+        problem
+            .parse_input("L\n\nAAA = (BBB, BBB)\nBBB = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)")
+            .unwrap();
+        assert!(problem.part_1().is_err());
     }
 }
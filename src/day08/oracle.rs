@@ -0,0 +1,68 @@
+//! Reference answers that walk the network one step at a time, all ghosts at once for
+//! part 2.
+
+use std::collections::HashMap;
+
+fn network(input: &str) -> (Vec<char>, HashMap<&str, (&str, &str)>) {
+    let (path, nodes) = input.split_once("\n\n").unwrap();
+    let nodes = nodes
+        .lines()
+        .map(|l| (&l[0..3], (&l[7..10], &l[12..15])))
+        .collect();
+    (path.trim().chars().collect(), nodes)
+}
+
+pub fn part_1(input: &str) -> u64 {
+    part_1_within(input, u64::MAX).unwrap()
+}
+
+/// [`part_1`], giving up after `limit` steps.
+pub fn part_1_within(input: &str, limit: u64) -> Option<u64> {
+    let (path, nodes) = network(input);
+    let mut current = "AAA";
+    let mut steps = 0;
+    while current != "ZZZ" {
+        if steps as u64 == limit {
+            return None;
+        }
+        let (left, right) = nodes[current];
+        current = if path[steps % path.len()] == 'L' {
+            left
+        } else {
+            right
+        };
+        steps += 1;
+    }
+    Some(steps as u64)
+}
+
+pub fn part_2(input: &str) -> u64 {
+    part_2_within(input, u64::MAX).unwrap()
+}
+
+/// [`part_2`], giving up after `limit` steps. Without a limit this never returns when the
+/// ghosts do not all arrive at once.
+pub fn part_2_within(input: &str, limit: u64) -> Option<u64> {
+    let (path, nodes) = network(input);
+    let mut ghosts = nodes
+        .keys()
+        .filter(|k| k.ends_with('A'))
+        .copied()
+        .collect::<Vec<_>>();
+    let mut steps = 0;
+    while !ghosts.iter().all(|g| g.ends_with('Z')) {
+        if steps as u64 == limit {
+            return None;
+        }
+        for ghost in ghosts.iter_mut() {
+            let (left, right) = nodes[*ghost];
+            *ghost = if path[steps % path.len()] == 'L' {
+                left
+            } else {
+                right
+            };
+        }
+        steps += 1;
+    }
+    Some(steps as u64)
+}
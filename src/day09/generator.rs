@@ -0,0 +1,32 @@
+use crate::rng::Rng;
+
+/// `size` sequences, each the values of a random polynomial of degree at most four with small
+/// coefficients at `x = 0, 1, ...`. Sequences are long enough for the differences to reach
+/// zero, except for the occasional very short one.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..size {
+        let degree = rng.range_inclusive(0..=4) as usize;
+        let coefficients = (0..=degree)
+            .map(|_| rng.range_inclusive(0..=20) as i64 - 10)
+            .collect::<Vec<_>>();
+        let len = if rng.chance(1, 10) {
+            rng.range_inclusive(1..=2)
+        } else {
+            rng.range_inclusive(degree as u64 + 2..=degree as u64 + 12)
+        };
+
+        let values = (0..len as i64)
+            .map(|x| {
+                coefficients
+                    .iter()
+                    .rev()
+                    .fold(0, |acc, c| acc * x + c)
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+        out.push_str(&values.join(" "));
+        out.push('\n');
+    }
+    out
+}
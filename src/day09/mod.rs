@@ -1,81 +1,189 @@
 use std::str::FromStr;
 
+use color_eyre::eyre::Result;
+use color_eyre::Report;
 use itertools::Itertools;
 
+use crate::arith::{OrOverflow, TryCheckedIterator};
+use crate::inspect::Stats;
+use crate::parallel::prelude::*;
+use crate::parsing::{parse_all, parse_lines, signed_list};
+use crate::rng::Rng;
+use crate::stream::LineSolver;
 use crate::AoCProblem;
 
+pub mod generator;
+pub mod oracle;
+
 #[derive(Default)]
 pub struct Day09 {
     data: Vec<NumberSequence>,
 }
 
 impl AoCProblem for Day09 {
-    fn parse_input(&mut self, input: &str) {
-        self.data = input.lines().map(|l| l.parse().unwrap()).collect()
+    fn parse_input(&mut self, input: &str) -> Result<()> {
+        self.data = parse_lines(input)?;
+        Ok(())
     }
 
-    fn parse_input_default(&mut self) {
-        self.parse_input(include_str!("../../inputs/day09.txt"));
+    fn puzzle_input(&self) -> &'static str {
+        include_str!("../../inputs/day09.txt")
     }
 
-    fn part_1(&self) -> Option<String> {
-        Some(
-            self.data
-                .iter()
-                .map(|s| s.get_next_value())
-                .sum::<i32>()
-                .to_string(),
-        )
+    fn part_1(&self) -> Result<Option<String>> {
+        let sum = self
+            .data
+            .maybe_par_iter()
+            .map(|s| s.get_next_value())
+            .sequential()
+            .try_checked_sum()?;
+
+        Ok(Some(sum.to_string()))
     }
 
-    fn part_2(&self) -> Option<String> {
-        Some(
-            self.data
-                .iter()
-                .map(|s| NumberSequence {
-                    numbers: s.numbers.iter().rev().copied().collect_vec(),
-                })
-                .map(|s| s.get_next_value())
-                .sum::<i32>()
-                .to_string(),
-        )
+    fn part_2(&self) -> Result<Option<String>> {
+        let sum = self
+            .data
+            .maybe_par_iter()
+            .map(|s| NumberSequence {
+                numbers: s.numbers.iter().rev().copied().collect_vec(),
+            })
+            .map(|s| s.get_next_value())
+            .sequential()
+            .try_checked_sum()?;
+
+        Ok(Some(sum.to_string()))
     }
 
     fn get_day_name(&self) -> String {
         "Day 09: Mirage Maintenance".into()
     }
+
+    fn solver_version(&self) -> &'static str {
+        "1"
+    }
+
+    fn inspect(&self, stats: &mut Stats) {
+        stats.fact("sequences", self.data.len());
+        stats.range("length", self.data.iter().map(|s| s.numbers.len()));
+        stats.range("value", self.data.iter().flat_map(|s| &s.numbers));
+        match self
+            .data
+            .iter()
+            .map(NumberSequence::largest_magnitude)
+            .max()
+        {
+            Some(Some(magnitude)) => {
+                stats.fits::<i64, _>("largest difference or extrapolation", magnitude)
+            }
+            Some(None) => stats.overflow("a difference is more than an i128 holds".into()),
+            None => {}
+        }
+    }
+
+    fn generate_input(&self, rng: &mut Rng, size: usize) -> String {
+        generator::generate(rng, size)
+    }
+
+    fn line_solver(&self) -> Option<Box<dyn LineSolver>> {
+        Some(Box::<Streaming>::default())
+    }
+
+    fn reference_answers(&self, input: &str) -> (Option<String>, Option<String>) {
+        (
+            Some(oracle::part_1(input).to_string()),
+            Some(oracle::part_2(input).to_string()),
+        )
+    }
+}
+
+#[derive(Default)]
+struct Streaming {
+    part_1: i64,
+    part_2: i64,
+}
+
+impl LineSolver for Streaming {
+    fn feed(&mut self, line: &str) -> Result<()> {
+        if line.trim().is_empty() {
+            return Ok(());
+        }
+        let mut sequence = line.parse::<NumberSequence>()?;
+        self.part_1 = self
+            .part_1
+            .checked_add(sequence.get_next_value()?)
+            .or_overflow("a sum")?;
+        sequence.numbers.reverse();
+        self.part_2 = self
+            .part_2
+            .checked_add(sequence.get_next_value()?)
+            .or_overflow("a sum")?;
+        Ok(())
+    }
+
+    fn finish(self: Box<Self>) -> Result<(Option<String>, Option<String>)> {
+        Ok((Some(self.part_1.to_string()), Some(self.part_2.to_string())))
+    }
 }
 
 #[derive(Debug, Default)]
 struct NumberSequence {
-    numbers: Vec<i32>,
+    numbers: Vec<i64>,
 }
 
 impl NumberSequence {
-    fn get_next_value(&self) -> i32 {
+    fn get_next_value(&self) -> Result<i64> {
         let mut seq = self.numbers.clone();
         let mut helper = Vec::with_capacity(seq.len());
         let mut cum_sum = 0;
 
         while !seq.iter().all(|n| *n == 0) {
-            cum_sum += seq.last().unwrap_or(&0);
+            cum_sum = i64::checked_add(cum_sum, *seq.last().unwrap_or(&0))
+                .or_overflow("the next value")?;
 
             helper.clear();
-            helper.extend(seq.windows(2).map(|w| w[1] - w[0]));
+            for w in seq.windows(2) {
+                helper.push(w[1].checked_sub(w[0]).or_overflow("a difference")?);
+            }
             seq.clear();
             seq.extend(helper.iter());
         }
 
-        cum_sum
+        Ok(cum_sum)
+    }
+
+    /// The largest absolute value in the difference table or among the extrapolated values
+    /// in either direction, `None` if even an `i128` cannot hold them.
+    fn largest_magnitude(&self) -> Option<i128> {
+        let mut row = self.numbers.iter().map(|&n| n as i128).collect_vec();
+        let (mut next, mut previous, mut sign) = (0_i128, 0_i128, 1);
+        let mut largest = 0_i128;
+        while row.iter().any(|&n| n != 0) {
+            next = next.checked_add(*row.last()?)?;
+            previous = previous.checked_add(sign * row[0])?;
+            sign = -sign;
+            for n in &row {
+                largest = largest.max(n.checked_abs()?);
+            }
+            row = row
+                .windows(2)
+                .map(|w| w[1].checked_sub(w[0]))
+                .collect::<Option<_>>()?;
+        }
+
+        largest
+            .max(next.checked_abs()?)
+            .max(previous.checked_abs()?)
+            .into()
     }
 }
 
 impl FromStr for NumberSequence {
-    type Err = !;
+    type Err = Report;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(Self {
-            numbers: s.split_whitespace().map(|l| l.parse().unwrap()).collect(),
+            numbers: parse_all(s, signed_list)?,
         })
     }
 }
@@ -92,8 +200,8 @@ mod tests {
 1 3 6 10 15 21
 10 13 16 21 30 45";
         let mut day09 = super::Day09::default();
-        day09.parse_input(input);
-        assert_eq!(day09.part_1(), Some("114".into()))
+        day09.parse_input(input).unwrap();
+        assert_eq!(day09.part_1().unwrap(), Some("114".into()))
     }
 
     #[test]
@@ -102,7 +210,19 @@ mod tests {
 1 3 6 10 15 21
 10 13 16 21 30 45";
         let mut day09 = super::Day09::default();
-        day09.parse_input(input);
-        assert_eq!(day09.part_2(), Some("2".into()))
+        day09.parse_input(input).unwrap();
+        assert_eq!(day09.part_2().unwrap(), Some("2".into()))
+    }
+
+    #[test]
+    fn overflow_test() {
+        let mut day09 = super::Day09::default();
+        day09
+            .parse_input("-9223372036854775808 9223372036854775807")
+            .unwrap();
+        assert!(day09.part_1().is_err());
+
+        day09.parse_input("0 4611686018427387903").unwrap();
+        assert_eq!(day09.part_1().unwrap(), Some("9223372036854775806".into()));
     }
 }
@@ -0,0 +1,40 @@
+//! Reference answers that skip the difference tables and use the closed form of Newton's
+//! forward differences: the next value of `y_0..y_{n-1}` is
+//! `sum (-1)^(n-1-i) * C(n, i) * y_i`.
+
+fn binomial(n: i128, k: i128) -> i128 {
+    (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+}
+
+fn next_value(values: &[i128]) -> i128 {
+    let n = values.len() as i128;
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, y)| {
+            let sign = if (n - 1 - i as i128) % 2 == 0 { 1 } else { -1 };
+            sign * binomial(n, i as i128) * y
+        })
+        .sum()
+}
+
+fn sequences(input: &str) -> Vec<Vec<i128>> {
+    input
+        .lines()
+        .map(|l| l.split_whitespace().map(|n| n.parse().unwrap()).collect())
+        .collect()
+}
+
+pub fn part_1(input: &str) -> i128 {
+    sequences(input).iter().map(|s| next_value(s)).sum()
+}
+
+pub fn part_2(input: &str) -> i128 {
+    sequences(input)
+        .into_iter()
+        .map(|mut s| {
+            s.reverse();
+            next_value(&s)
+        })
+        .sum()
+}
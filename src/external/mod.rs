@@ -0,0 +1,134 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{eyre, Result, WrapErr};
+
+/// Another solver run as a child process. It gets the puzzle input on stdin and prints the
+/// part 1 answer on its first line of output and the part 2 answer on the second. A missing,
+/// empty or `-` line means it has no answer for that part, and anything after the second
+/// line is ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct External {
+    program: String,
+    args: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalRun {
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+    /// Wall time from spawning the process until it exited, startup included.
+    pub time: Duration,
+}
+
+impl External {
+    pub fn new(
+        program: impl Into<String>,
+        args: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        Self {
+            program: program.into(),
+            args: args.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Splits `command` on whitespace, there is no quoting. `{day}` anywhere in it becomes the
+    /// two digit day number when the command is run, e.g. `python3 day{day}.py`.
+    pub fn parse(command: &str) -> Result<Self> {
+        let mut words = command.split_whitespace();
+        let program = words.next().ok_or_else(|| eyre!("the command is empty"))?;
+        Ok(Self::new(program, words))
+    }
+
+    pub fn run(&self, day: u32, input: &str) -> Result<ExternalRun> {
+        let day = format!("{:02}", day);
+        let program = self.program.replace("{day}", &day);
+
+        let start = Instant::now();
+        let mut child = Command::new(&program)
+            .args(self.args.iter().map(|a| a.replace("{day}", &day)))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .wrap_err_with(|| format!("starting {}", program))?;
+
+        // Writing from another thread, a solver that prints while it reads could otherwise
+        // fill up its stdout pipe and block both of us.
+        let mut stdin = child.stdin.take().unwrap();
+        let input = input.to_string();
+        let writer = thread::spawn(move || stdin.write_all(input.as_bytes()));
+        let output = child.wait_with_output()?;
+        let time = start.elapsed();
+        // A solver is free to stop reading early, a broken pipe here is not an error.
+        let _ = writer.join();
+
+        if !output.status.success() {
+            return Err(eyre!(
+                "{} failed with {}: {}",
+                program,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        let stdout = String::from_utf8(output.stdout)
+            .wrap_err_with(|| format!("the output of {} is not UTF-8", program))?;
+        let (part_1, part_2) = answer_lines(&stdout);
+
+        Ok(ExternalRun {
+            part_1,
+            part_2,
+            time,
+        })
+    }
+}
+
+/// Reads the two answers of the [`External`] protocol from `text`.
+pub fn answer_lines(text: &str) -> (Option<String>, Option<String>) {
+    let mut lines = text.lines().map(str::trim);
+    let mut answer = || {
+        lines
+            .next()
+            .filter(|l| !l.is_empty() && *l != "-")
+            .map(String::from)
+    };
+    (answer(), answer())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn sh(script: &str) -> External {
+        External::new("sh", ["-c", script])
+    }
+
+    #[test]
+    fn answers_from_stdout() {
+        let run = sh("wc -l; echo -").run(5, "a\nb\nc\n").unwrap();
+        assert_eq!(run.part_1.as_deref(), Some("3"));
+        assert_eq!(run.part_2, None);
+
+        let run = sh("echo day {day}").run(7, "").unwrap();
+        assert_eq!(run.part_1.as_deref(), Some("day 07"));
+    }
+
+    #[test]
+    fn failures() {
+        let err = sh("echo oops >&2; exit 3").run(1, "").unwrap_err();
+        assert!(err.to_string().contains("oops"), "{}", err);
+        assert!(External::new("./does-not-exist", Vec::<String>::new())
+            .run(1, "")
+            .is_err());
+        assert!(External::parse("  ").is_err());
+        assert_eq!(
+            External::parse("python3  day{day}.py").unwrap(),
+            External::new("python3", ["day{day}.py"])
+        );
+    }
+}
@@ -0,0 +1,498 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+pub type Cost = u64;
+
+/// Anything that can enumerate the weighted out-edges of a node.
+pub trait Graph {
+    type Node: Clone + Eq + Hash;
+
+    fn neighbours(&self, node: &Self::Node) -> Vec<(Self::Node, Cost)>;
+}
+
+/// A graph that additionally knows all of its nodes, which the whole-graph algorithms need.
+pub trait FiniteGraph: Graph {
+    fn nodes(&self) -> Vec<Self::Node>;
+}
+
+/// Explicit adjacency list, nodes are kept in insertion order so results are deterministic.
+#[derive(Debug, Clone)]
+pub struct AdjacencyList<N> {
+    nodes: Vec<N>,
+    index: HashMap<N, usize>,
+    edges: Vec<Vec<(N, Cost)>>,
+}
+
+impl<N> Default for AdjacencyList<N> {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            index: HashMap::new(),
+            edges: Vec::new(),
+        }
+    }
+}
+
+impl<N: Clone + Eq + Hash> AdjacencyList<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Explores everything reachable from `starts` in `graph` and stores it explicitly.
+    pub fn from_graph<G: Graph<Node = N>>(graph: &G, starts: impl IntoIterator<Item = N>) -> Self {
+        let mut list = Self::new();
+        let mut queue = VecDeque::new();
+        for start in starts {
+            if !list.index.contains_key(&start) {
+                list.add_node(start.clone());
+                queue.push_back(start);
+            }
+        }
+
+        while let Some(node) = queue.pop_front() {
+            for (next, cost) in graph.neighbours(&node) {
+                if !list.index.contains_key(&next) {
+                    queue.push_back(next.clone());
+                }
+                list.add_edge(node.clone(), next, cost);
+            }
+        }
+
+        list
+    }
+
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(&i) = self.index.get(&node) {
+            return i;
+        }
+        self.index.insert(node.clone(), self.nodes.len());
+        self.nodes.push(node);
+        self.edges.push(Vec::new());
+        self.nodes.len() - 1
+    }
+
+    pub fn add_edge(&mut self, from: N, to: N, cost: Cost) {
+        let from = self.add_node(from);
+        self.add_node(to.clone());
+        self.edges[from].push((to, cost));
+    }
+
+    pub fn add_undirected_edge(&mut self, a: N, b: N, cost: Cost) {
+        self.add_edge(a.clone(), b.clone(), cost);
+        self.add_edge(b, a, cost);
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.index.contains_key(node)
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+impl<N: Clone + Eq + Hash> Graph for AdjacencyList<N> {
+    type Node = N;
+
+    fn neighbours(&self, node: &N) -> Vec<(N, Cost)> {
+        self.index
+            .get(node)
+            .map(|&i| self.edges[i].clone())
+            .unwrap_or_default()
+    }
+}
+
+impl<N: Clone + Eq + Hash> FiniteGraph for AdjacencyList<N> {
+    fn nodes(&self) -> Vec<N> {
+        self.nodes.clone()
+    }
+}
+
+/// Implicit graph whose edges are computed on demand, e.g. the four neighbours of a grid cell.
+pub struct FnGraph<N, F> {
+    neighbours: F,
+    _node: std::marker::PhantomData<N>,
+}
+
+impl<N, F, I> FnGraph<N, F>
+where
+    F: Fn(&N) -> I,
+    I: IntoIterator<Item = (N, Cost)>,
+{
+    pub const fn new(neighbours: F) -> Self {
+        Self {
+            neighbours,
+            _node: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<N, F, I> Graph for FnGraph<N, F>
+where
+    N: Clone + Eq + Hash,
+    F: Fn(&N) -> I,
+    I: IntoIterator<Item = (N, Cost)>,
+{
+    type Node = N;
+
+    fn neighbours(&self, node: &N) -> Vec<(N, Cost)> {
+        (self.neighbours)(node).into_iter().collect()
+    }
+}
+
+fn reconstruct_path<N: Clone + Eq + Hash>(parents: &HashMap<N, N>, goal: N) -> Vec<N> {
+    let mut path = vec![goal];
+    while let Some(parent) = parents.get(path.last().unwrap()) {
+        path.push(parent.clone());
+    }
+    path.reverse();
+    path
+}
+
+/// Number of edges on the shortest path from `start` to every reachable node, ignoring costs.
+pub fn bfs<G: Graph>(graph: &G, start: G::Node) -> HashMap<G::Node, usize> {
+    let mut distances = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(node) = queue.pop_front() {
+        let distance = distances[&node];
+        for (next, _) in graph.neighbours(&node) {
+            if !distances.contains_key(&next) {
+                distances.insert(next.clone(), distance + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    distances
+}
+
+/// Path with the fewest edges from `start` to the first node satisfying `is_goal`.
+pub fn bfs_path<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    is_goal: impl Fn(&G::Node) -> bool,
+) -> Option<Vec<G::Node>> {
+    let mut parents = HashMap::new();
+    let mut seen = HashSet::from([start.clone()]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(node) = queue.pop_front() {
+        if is_goal(&node) {
+            return Some(reconstruct_path(&parents, node));
+        }
+        for (next, _) in graph.neighbours(&node) {
+            if seen.insert(next.clone()) {
+                parents.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+
+    None
+}
+
+/// Cheapest cost from `start` to every reachable node.
+pub fn dijkstra<G: Graph>(graph: &G, start: G::Node) -> HashMap<G::Node, Cost> {
+    search(graph, start, |_| false, |_| 0).0
+}
+
+/// Cheapest path from `start` to the first node satisfying `is_goal`.
+pub fn shortest_path<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    is_goal: impl Fn(&G::Node) -> bool,
+) -> Option<(Cost, Vec<G::Node>)> {
+    astar(graph, start, is_goal, |_| 0)
+}
+
+/// Cheapest path using `heuristic`, which must never overestimate the remaining cost.
+pub fn astar<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    is_goal: impl Fn(&G::Node) -> bool,
+    heuristic: impl Fn(&G::Node) -> Cost,
+) -> Option<(Cost, Vec<G::Node>)> {
+    let (costs, parents, goal) = search(graph, start, is_goal, heuristic);
+    let goal = goal?;
+    Some((costs[&goal], reconstruct_path(&parents, goal)))
+}
+
+type SearchResult<N> = (HashMap<N, Cost>, HashMap<N, N>, Option<N>);
+
+fn search<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    is_goal: impl Fn(&G::Node) -> bool,
+    heuristic: impl Fn(&G::Node) -> Cost,
+) -> SearchResult<G::Node> {
+    // The heap stores indices into `arena` so that nodes don't have to be `Ord`.
+    let mut arena = vec![start.clone()];
+    let mut costs = HashMap::from([(start.clone(), 0)]);
+    let mut parents = HashMap::new();
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), 0, 0_usize))]);
+
+    while let Some(Reverse((_, cost, id))) = heap.pop() {
+        let node = arena[id].clone();
+        if cost > costs[&node] {
+            continue;
+        }
+        if is_goal(&node) {
+            return (costs, parents, Some(node));
+        }
+
+        for (next, edge) in graph.neighbours(&node) {
+            let next_cost = cost + edge;
+            if costs.get(&next).is_none_or(|&c| next_cost < c) {
+                costs.insert(next.clone(), next_cost);
+                parents.insert(next.clone(), node.clone());
+                heap.push(Reverse((
+                    next_cost + heuristic(&next),
+                    next_cost,
+                    arena.len(),
+                )));
+                arena.push(next);
+            }
+        }
+    }
+
+    (costs, parents, None)
+}
+
+/// Nodes ordered so that every edge points forward, or `None` if the graph has a cycle.
+pub fn topological_sort<G: FiniteGraph>(graph: &G) -> Option<Vec<G::Node>> {
+    let nodes = graph.nodes();
+    let mut in_degree: HashMap<G::Node, usize> = nodes.iter().map(|n| (n.clone(), 0)).collect();
+    for node in &nodes {
+        for (next, _) in graph.neighbours(node) {
+            *in_degree.entry(next).or_default() += 1;
+        }
+    }
+
+    let mut queue = nodes
+        .iter()
+        .filter(|n| in_degree[*n] == 0)
+        .cloned()
+        .collect::<VecDeque<_>>();
+    let mut order = Vec::with_capacity(in_degree.len());
+    while let Some(node) = queue.pop_front() {
+        for (next, _) in graph.neighbours(&node) {
+            let degree = in_degree.get_mut(&next).unwrap();
+            *degree -= 1;
+            if *degree == 0 {
+                queue.push_back(next);
+            }
+        }
+        order.push(node);
+    }
+
+    (order.len() == in_degree.len()).then_some(order)
+}
+
+/// Tarjan's algorithm, components are returned in reverse topological order.
+pub fn strongly_connected_components<G: FiniteGraph>(graph: &G) -> Vec<Vec<G::Node>> {
+    struct State<N> {
+        index: HashMap<N, usize>,
+        low_link: HashMap<N, usize>,
+        on_stack: HashMap<N, bool>,
+        stack: Vec<N>,
+        components: Vec<Vec<N>>,
+    }
+
+    let mut state = State {
+        index: HashMap::new(),
+        low_link: HashMap::new(),
+        on_stack: HashMap::new(),
+        stack: Vec::new(),
+        components: Vec::new(),
+    };
+
+    for root in graph.nodes() {
+        if state.index.contains_key(&root) {
+            continue;
+        }
+
+        // Explicit call stack of (node, its neighbours, next neighbour to visit) to avoid
+        // overflowing the real one on long chains.
+        let mut call_stack = vec![(root.clone(), graph.neighbours(&root), 0)];
+        let i = state.index.len();
+        state.index.insert(root.clone(), i);
+        state.low_link.insert(root.clone(), i);
+        state.on_stack.insert(root.clone(), true);
+        state.stack.push(root);
+
+        while let Some((node, neighbours, next)) = call_stack.last_mut() {
+            if let Some((child, _)) = neighbours.get(*next).cloned() {
+                *next += 1;
+                if !state.index.contains_key(&child) {
+                    let i = state.index.len();
+                    state.index.insert(child.clone(), i);
+                    state.low_link.insert(child.clone(), i);
+                    state.on_stack.insert(child.clone(), true);
+                    state.stack.push(child.clone());
+                    let child_neighbours = graph.neighbours(&child);
+                    call_stack.push((child, child_neighbours, 0));
+                } else if state.on_stack[&child] {
+                    let low = state.low_link[node].min(state.index[&child]);
+                    state.low_link.insert(node.clone(), low);
+                }
+                continue;
+            }
+
+            let node = node.clone();
+            call_stack.pop();
+            if let Some((parent, _, _)) = call_stack.last() {
+                let low = state.low_link[parent].min(state.low_link[&node]);
+                state.low_link.insert(parent.clone(), low);
+            }
+
+            if state.low_link[&node] == state.index[&node] {
+                let mut component = Vec::new();
+                loop {
+                    let member = state.stack.pop().unwrap();
+                    state.on_stack.insert(member.clone(), false);
+                    let done = member == node;
+                    component.push(member);
+                    if done {
+                        break;
+                    }
+                }
+                state.components.push(component);
+            }
+        }
+    }
+
+    state.components
+}
+
+/// Most expensive path from `start` to every reachable node, or `None` if the graph has a cycle.
+pub fn longest_path_dag<G: FiniteGraph>(
+    graph: &G,
+    start: G::Node,
+) -> Option<HashMap<G::Node, Cost>> {
+    let order = topological_sort(graph)?;
+    let mut distances = HashMap::from([(start, 0)]);
+
+    for node in order {
+        let Some(&distance) = distances.get(&node) else {
+            continue;
+        };
+        for (next, cost) in graph.neighbours(&node) {
+            let entry = distances.entry(next).or_insert(0);
+            *entry = (*entry).max(distance + cost);
+        }
+    }
+
+    Some(distances)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn diamond() -> AdjacencyList<&'static str> {
+        let mut graph = AdjacencyList::new();
+        graph.add_edge("a", "b", 1);
+        graph.add_edge("a", "c", 4);
+        graph.add_edge("b", "c", 2);
+        graph.add_edge("b", "d", 6);
+        graph.add_edge("c", "d", 3);
+        graph
+    }
+
+    type Point = (i32, i32);
+
+    fn grid(width: i32, height: i32) -> impl Graph<Node = Point> {
+        FnGraph::new(move |&(x, y): &(i32, i32)| {
+            [(0, 1), (1, 0), (0, -1), (-1, 0)]
+                .iter()
+                .map(|(dx, dy)| (x + dx, y + dy))
+                .filter(|&(x, y)| x >= 0 && y >= 0 && x < width && y < height)
+                .map(|p| (p, 1))
+                .collect::<Vec<_>>()
+        })
+    }
+
+    #[test]
+    fn bfs_counts_edges() {
+        let distances = bfs(&diamond(), "a");
+        assert_eq!(distances["d"], 2);
+        assert_eq!(distances["c"], 1);
+
+        let path = bfs_path(&grid(5, 5), (0, 0), |&p| p == (4, 4)).unwrap();
+        assert_eq!(path.len(), 9);
+    }
+
+    #[test]
+    fn dijkstra_and_astar_agree() {
+        let graph = diamond();
+        assert_eq!(dijkstra(&graph, "a")["d"], 6);
+        assert_eq!(
+            shortest_path(&graph, "a", |&n| n == "d"),
+            Some((6, vec!["a", "b", "c", "d"]))
+        );
+
+        let (cost, _) = astar(
+            &grid(10, 10),
+            (0, 0),
+            |&p| p == (9, 7),
+            |&(x, y)| ((9 - x).abs() + (7 - y).abs()) as Cost,
+        )
+        .unwrap();
+        assert_eq!(cost, 16);
+        assert_eq!(shortest_path(&grid(3, 3), (0, 0), |&p| p == (5, 5)), None);
+    }
+
+    #[test]
+    fn topological_order() {
+        let order = topological_sort(&diamond()).unwrap();
+        assert_eq!(order, vec!["a", "b", "c", "d"]);
+
+        let mut cyclic = diamond();
+        cyclic.add_edge("d", "a", 1);
+        assert_eq!(topological_sort(&cyclic), None);
+        assert_eq!(longest_path_dag(&cyclic, "a"), None);
+    }
+
+    #[test]
+    fn longest_path() {
+        let distances = longest_path_dag(&diamond(), "a").unwrap();
+        assert_eq!(distances["d"], 7);
+        assert_eq!(distances["c"], 4);
+    }
+
+    #[test]
+    fn components() {
+        let mut graph = AdjacencyList::new();
+        graph.add_edge(1, 2, 1);
+        graph.add_edge(2, 3, 1);
+        graph.add_edge(3, 1, 1);
+        graph.add_edge(3, 4, 1);
+        graph.add_edge(4, 5, 1);
+        graph.add_edge(5, 4, 1);
+        graph.add_node(6);
+
+        let components = strongly_connected_components(&graph)
+            .into_iter()
+            .map(|mut c| {
+                c.sort();
+                c
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(components, vec![vec![4, 5], vec![1, 2, 3], vec![6]]);
+    }
+
+    #[test]
+    fn explicit_from_implicit() {
+        let graph = AdjacencyList::from_graph(&grid(3, 2), [(0, 0)]);
+        assert_eq!(graph.len(), 6);
+        assert_eq!(graph.neighbours(&(0, 0)).len(), 2);
+    }
+}
@@ -0,0 +1,141 @@
+use std::any::type_name;
+use std::fmt::Display;
+
+use color_eyre::eyre::Result;
+use itertools::{Itertools, MinMaxResult};
+
+use crate::AoCProblem;
+
+/// What is known about an input after parsing it, in the order it was found out.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub facts: Vec<(String, String)>,
+    /// Values the solver's integer types cannot hold.
+    pub overflows: Vec<String>,
+}
+
+impl Stats {
+    pub fn fact(&mut self, name: &str, value: impl Display) {
+        self.facts.push((name.into(), value.to_string()));
+    }
+
+    /// The smallest and largest of `values` as `min..=max`.
+    pub fn range<T: PartialOrd + Display>(
+        &mut self,
+        name: &str,
+        values: impl IntoIterator<Item = T>,
+    ) {
+        match values.into_iter().minmax() {
+            MinMaxResult::NoElements => self.fact(name, "none"),
+            MinMaxResult::OneElement(v) => self.fact(name, format!("{}..={}", v, v)),
+            MinMaxResult::MinMax(min, max) => self.fact(name, format!("{}..={}", min, max)),
+        }
+    }
+
+    /// Records `value` as `name` and flags it when it does not fit in `T`.
+    pub fn fits<T: TryFrom<V>, V: Display + Copy>(&mut self, name: &str, value: V) {
+        self.fact(name, value);
+        if T::try_from(value).is_err() {
+            self.overflow(format!(
+                "{} is {}, more than a {} holds",
+                name,
+                value,
+                type_name::<T>()
+            ));
+        }
+    }
+
+    pub fn overflow(&mut self, message: String) {
+        self.overflows.push(message);
+    }
+
+    pub fn print(&self) {
+        let width = self.facts.iter().map(|(n, _)| n.len()).max().unwrap_or(0);
+        for (name, value) in &self.facts {
+            println!("\t{:<width$}  {}", name, value, width = width);
+        }
+        println!();
+        for overflow in &self.overflows {
+            println!("\tOVERFLOW: {}", overflow);
+        }
+        if self.overflows.is_empty() {
+            println!("\tNothing overflows");
+        }
+        println!();
+    }
+}
+
+/// Parses `input` and describes it, starting with what every day has in common.
+pub fn inspect(day: &mut dyn AoCProblem, input: &str) -> Result<Stats> {
+    day.parse_input(input)?;
+
+    let mut stats = Stats::default();
+    stats.fact("bytes", input.len());
+    stats.fact("lines", input.lines().count());
+    day.inspect(&mut stats);
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::day;
+
+    use super::*;
+
+    fn fact<'a>(stats: &'a Stats, name: &str) -> &'a str {
+        let (_, value) = stats.facts.iter().find(|(n, _)| n == name).unwrap();
+        value
+    }
+
+    #[test]
+    fn ranges_and_overflows() {
+        let mut stats = Stats::default();
+        stats.range("empty", Vec::<u32>::new());
+        stats.range("one", [4]);
+        stats.range("many", [3, -1, 7]);
+        stats.fits::<u32, _>("small", 7_u64);
+        stats.fits::<u32, _>("large", 1_u64 << 40);
+        assert_eq!(fact(&stats, "empty"), "none");
+        assert_eq!(fact(&stats, "one"), "4..=4");
+        assert_eq!(fact(&stats, "many"), "-1..=7");
+        assert_eq!(
+            stats.overflows,
+            ["large is 1099511627776, more than a u32 holds"]
+        );
+    }
+
+    #[test]
+    fn every_day_describes_its_input() {
+        for n in 1..=crate::days_implemented() {
+            let mut day = day(n).unwrap();
+            let input = day.generate_input(&mut crate::rng::Rng::new(n as u64), 30);
+            let stats = inspect(&mut *day, &input).unwrap();
+            assert!(stats.facts.len() > 2, "day {}", n);
+            assert!(
+                stats.overflows.is_empty(),
+                "day {}: {:?}",
+                n,
+                stats.overflows
+            );
+        }
+    }
+
+    #[test]
+    fn day09_differences_overflow() {
+        let input = format!("{} {} {}\n", i64::MAX, 0, i64::MAX);
+        let stats = inspect(&mut *day(9).unwrap(), &input).unwrap();
+        assert_eq!(stats.overflows.len(), 1, "{:?}", stats.overflows);
+    }
+
+    #[test]
+    fn day04_number_sets() {
+        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53\n\
+                     Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19\n";
+        let stats = inspect(&mut *day(4).unwrap(), input).unwrap();
+        assert_eq!(fact(&stats, "winning numbers"), "5..=5");
+        assert_eq!(fact(&stats, "scratched numbers"), "8..=8");
+        assert_eq!(fact(&stats, "matches"), "2..=4");
+    }
+}
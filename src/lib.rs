@@ -0,0 +1,244 @@
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{eyre, Result};
+use color_eyre::Report;
+
+use crate::inspect::Stats;
+use crate::reduce::Chunk;
+use crate::rng::Rng;
+use crate::stream::LineSolver;
+
+pub mod arith;
+pub mod batch;
+pub mod cache;
+pub mod cli;
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod external;
+pub mod graph;
+pub mod inspect;
+pub mod memo;
+pub mod parallel;
+pub mod parsing;
+pub mod reduce;
+pub mod rng;
+pub mod stream;
+
+pub trait AoCProblem {
+    fn parse_input(&mut self, input: &str) -> Result<()>;
+    fn puzzle_input(&self) -> &'static str;
+    fn part_1(&self) -> Result<Option<String>>;
+    fn part_2(&self) -> Result<Option<String>>;
+    fn get_day_name(&self) -> String;
+    /// Identifies the solver in the answer cache, changing it invalidates the cached answers.
+    /// Any change that can change an answer has to come with a new version.
+    fn solver_version(&self) -> &'static str;
+
+    fn parse_input_default(&mut self) -> Result<()> {
+        self.parse_input(self.puzzle_input())
+    }
+
+    /// A valid input of roughly `size` items (lines, cards, races, ...), the same for the same
+    /// `rng` seed.
+    fn generate_input(&self, rng: &mut Rng, size: usize) -> String;
+
+    /// Answers of the slow reference solver in the day's `oracle` module, where it has one.
+    fn reference_answers(&self, _input: &str) -> (Option<String>, Option<String>) {
+        (None, None)
+    }
+
+    /// How `reduce` may cut `input` up, every line can go by default.
+    fn reduction_chunks<'a>(&self, input: &'a str) -> Vec<Chunk<'a>> {
+        reduce::lines_where(input, |_| true)
+    }
+
+    /// Adds what is worth knowing about the parsed input to `stats`: sizes, ranges of the
+    /// numbers in it and anything too large for the types the solver uses.
+    fn inspect(&self, _stats: &mut Stats) {}
+
+    /// A solver that goes through the input line by line, for days whose answers allow it.
+    fn line_solver(&self) -> Option<Box<dyn LineSolver>> {
+        None
+    }
+
+    /// Names of the day's interchangeable solvers, the default one first.
+    fn variants(&self) -> &'static [&'static str] {
+        &["default"]
+    }
+
+    /// Switches to the solver called `name`, one of [`AoCProblem::variants`].
+    fn select_variant(&mut self, name: &str) -> Result<()> {
+        if name == self.variants()[0] {
+            Ok(())
+        } else {
+            Err(unknown_variant(self, name))
+        }
+    }
+
+    fn print_solution(&mut self) -> Result<Duration> {
+        print_timed(self, Self::parse_input_default)
+    }
+
+    /// Like [`AoCProblem::print_solution`], but on `input` instead of the puzzle input.
+    fn print_solution_for(&mut self, input: &str) -> Result<Duration> {
+        print_timed(self, |day| day.parse_input(input))
+    }
+}
+
+pub fn unknown_variant<P: AoCProblem + ?Sized>(day: &P, name: &str) -> Report {
+    eyre!(
+        "{} has no variant {:?}, expected one of: {}",
+        day.get_day_name(),
+        name,
+        day.variants().join(", ")
+    )
+}
+
+/// Both answers of a day together with how long each step took. Parts without an answer
+/// count as taking no time.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Solution {
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+    pub parse_time: Duration,
+    pub part_1_time: Duration,
+    pub part_2_time: Duration,
+    /// The answers come from the cache and the parse time is how long looking them up took.
+    pub cached: bool,
+}
+
+impl Solution {
+    /// Runs `parse` and then both parts of `day`.
+    pub fn measure<P: AoCProblem + ?Sized>(
+        day: &mut P,
+        parse: impl FnOnce(&mut P) -> Result<()>,
+    ) -> Result<Self> {
+        let start = Instant::now();
+        parse(day)?;
+        let parse_time = start.elapsed();
+
+        let start = Instant::now();
+        let part_1 = day.part_1()?;
+        let part_1_time = part_1.as_ref().map_or(Duration::ZERO, |_| start.elapsed());
+
+        let start = Instant::now();
+        let part_2 = day.part_2()?;
+        let part_2_time = part_2.as_ref().map_or(Duration::ZERO, |_| start.elapsed());
+
+        Ok(Self {
+            part_1,
+            part_2,
+            parse_time,
+            part_1_time,
+            part_2_time,
+            cached: false,
+        })
+    }
+
+    pub fn solve_time(&self) -> Duration {
+        self.part_1_time + self.part_2_time
+    }
+
+    pub fn total_time(&self) -> Duration {
+        self.parse_time + self.solve_time()
+    }
+
+    /// Prints the answers and timings below a day's name.
+    pub fn print(&self) {
+        if self.cached {
+            println!("\tAnswers from the cache\n");
+        } else {
+            println!("\tParsing input took: {:?}\n", self.parse_time);
+        }
+        for (part, answer, time) in [
+            (1, &self.part_1, self.part_1_time),
+            (2, &self.part_2, self.part_2_time),
+        ] {
+            if let Some(answer) = answer {
+                println!("\tPart {}: {}", part, answer);
+                if !self.cached {
+                    println!("\tPart {} took: {:?}\n", part, time);
+                }
+            }
+        }
+        if self.cached {
+            println!("\n\tTotal time: {:?} (cached)\n", self.total_time());
+        } else {
+            println!("\tTotal time: {:?}\n", self.total_time());
+        }
+    }
+}
+
+fn print_timed<P: AoCProblem + ?Sized>(
+    day: &mut P,
+    parse: impl FnOnce(&mut P) -> Result<()>,
+) -> Result<Duration> {
+    println!("{}:", day.get_day_name());
+    let solution = Solution::measure(day, parse)?;
+    solution.print();
+
+    Ok(solution.total_time())
+}
+
+/// Days are numbered from one up to this.
+pub const fn days_implemented() -> u32 {
+    9
+}
+
+/// Every implemented day, in order.
+pub fn days() -> Vec<Box<dyn AoCProblem>> {
+    (1..=days_implemented()).map(|n| day(n).unwrap()).collect()
+}
+
+pub fn day(number: u32) -> Result<Box<dyn AoCProblem>> {
+    Ok(match number {
+        1 => Box::<day01::Day01>::default(),
+        2 => Box::<day02::Day02>::default(),
+        3 => Box::<day03::Day03>::default(),
+        4 => Box::<day04::Day04>::default(),
+        5 => Box::<day05::Day05>::default(),
+        6 => Box::<day06::Day06>::default(),
+        7 => Box::<day07::Day07>::default(),
+        8 => Box::<day08::Day08>::default(),
+        9 => Box::<day09::Day09>::default(),
+        _ => return Err(eyre!("day {} is not implemented", number)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn every_day_matches_its_oracle() {
+        // The reference solvers are brute force, so some days only get small inputs.
+        let max_sizes = [10, 10, 12, 12, 6, 3, 10, 4, 10];
+        assert_eq!(max_sizes.len(), days_implemented() as usize);
+
+        for ((n, mut day), max_size) in (1..).zip(days()).zip(max_sizes) {
+            for seed in 0..200 {
+                let mut rng = Rng::new(seed);
+                let size = rng.range_inclusive(1..=max_size) as usize;
+                let input = day.generate_input(&mut rng, size);
+
+                day.parse_input(&input).unwrap();
+                assert_eq!(
+                    (day.part_1().unwrap(), day.part_2().unwrap()),
+                    day.reference_answers(&input),
+                    "day {}\n{}",
+                    n,
+                    input
+                );
+            }
+        }
+    }
+}
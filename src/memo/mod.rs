@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+/// Storage behind a [`Memo`].
+pub trait Cache<K, V> {
+    fn lookup(&self, key: &K) -> Option<&V>;
+    fn store(&mut self, key: K, value: V);
+    fn len(&self) -> usize;
+    fn clear(&mut self);
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HashCache<K, V>(HashMap<K, V>);
+
+impl<K, V> Default for HashCache<K, V> {
+    fn default() -> Self {
+        Self(HashMap::new())
+    }
+}
+
+impl<K: Eq + Hash, V> Cache<K, V> for HashCache<K, V> {
+    fn lookup(&self, key: &K) -> Option<&V> {
+        self.0.get(key)
+    }
+
+    fn store(&mut self, key: K, value: V) {
+        self.0.insert(key, value);
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+/// Cache for small integer keys, backed by a vector that grows to the largest key seen.
+#[derive(Debug, Clone)]
+pub struct DenseCache<V> {
+    slots: Vec<Option<V>>,
+    len: usize,
+}
+
+impl<V> Default for DenseCache<V> {
+    fn default() -> Self {
+        Self {
+            slots: Vec::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<V> Cache<usize, V> for DenseCache<V> {
+    fn lookup(&self, key: &usize) -> Option<&V> {
+        self.slots.get(*key)?.as_ref()
+    }
+
+    fn store(&mut self, key: usize, value: V) {
+        if key >= self.slots.len() {
+            self.slots.resize_with(key + 1, || None);
+        }
+        if self.slots[key].replace(value).is_none() {
+            self.len += 1;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn clear(&mut self) {
+        self.slots.clear();
+        self.len = 0;
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl CacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+/// Memoizes a recursive function. The memo is an ordinary value owned by the caller, the
+/// recursive function takes it as a parameter and wraps its body in
+/// [`Memo::get_or_insert_with`], see `fib` in the tests.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V, C = HashCache<K, V>> {
+    cache: C,
+    stats: CacheStats,
+    _entry: PhantomData<(K, V)>,
+}
+
+pub type HashMemo<K, V> = Memo<K, V, HashCache<K, V>>;
+pub type DenseMemo<V> = Memo<usize, V, DenseCache<V>>;
+
+impl<K, V, C: Default> Default for Memo<K, V, C> {
+    fn default() -> Self {
+        Self {
+            cache: C::default(),
+            stats: CacheStats::default(),
+            _entry: PhantomData,
+        }
+    }
+}
+
+impl<K, V: Clone, C: Cache<K, V> + Default> Memo<K, V, C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the cached value for `key`, or computes it with `f` and caches it. `f` gets the
+    /// memo back so it can recurse.
+    pub fn get_or_insert_with(&mut self, key: K, f: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.cache.lookup(&key) {
+            self.stats.hits += 1;
+            return value.clone();
+        }
+
+        self.stats.misses += 1;
+        let value = f(self);
+        self.cache.store(key, value.clone());
+        value
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.lookup(key)
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.stats = CacheStats::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fib(memo: &mut DenseMemo<u64>, n: usize) -> u64 {
+        memo.get_or_insert_with(n, |memo| {
+            if n < 2 {
+                n as u64
+            } else {
+                fib(memo, n - 1) + fib(memo, n - 2)
+            }
+        })
+    }
+
+    fn paths(memo: &mut HashMemo<(u32, u32), u64>, x: u32, y: u32) -> u64 {
+        memo.get_or_insert_with((x, y), |memo| {
+            if x == 0 || y == 0 {
+                1
+            } else {
+                paths(memo, x - 1, y) + paths(memo, x, y - 1)
+            }
+        })
+    }
+
+    #[test]
+    fn dense() {
+        let mut memo = DenseMemo::new();
+        assert_eq!(fib(&mut memo, 90), 2880067194370816120);
+        assert_eq!(memo.len(), 91);
+        assert_eq!(
+            memo.stats(),
+            CacheStats {
+                hits: 88,
+                misses: 91
+            }
+        );
+    }
+
+    #[test]
+    fn hashed() {
+        let mut memo = HashMemo::new();
+        assert_eq!(paths(&mut memo, 16, 16), 601080390);
+        assert_eq!(memo.get(&(1, 1)), Some(&2));
+        assert!(memo.stats().hit_rate() > 0.4);
+
+        memo.clear();
+        assert!(memo.is_empty());
+        assert_eq!(memo.stats().hit_rate(), 0.0);
+    }
+}
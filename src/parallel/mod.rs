@@ -0,0 +1,80 @@
+//! Data parallelism behind the `parallel` feature. [`MaybeParallel::maybe_par_iter`] is rayon's
+//! `par_iter` with the feature and a plain `iter` without it, [`maybe_par_lines`] the same for
+//! the lines of a string, so solvers written against them can only use adapters both kinds of
+//! iterator have: `map`, `filter`, `enumerate`, `collect` and the like. Checked sums and folds
+//! go after [`Sequential::sequential`].
+
+pub mod prelude {
+    pub use super::{maybe_par_lines, MaybeParallel, Sequential};
+    #[cfg(feature = "parallel")]
+    pub use rayon::prelude::*;
+}
+
+pub trait MaybeParallel<'a> {
+    type Iter;
+
+    fn maybe_par_iter(&'a self) -> Self::Iter;
+}
+
+impl<'a, T: Sync + 'a> MaybeParallel<'a> for [T] {
+    #[cfg(feature = "parallel")]
+    type Iter = rayon::slice::Iter<'a, T>;
+    #[cfg(not(feature = "parallel"))]
+    type Iter = std::slice::Iter<'a, T>;
+
+    fn maybe_par_iter(&'a self) -> Self::Iter {
+        #[cfg(feature = "parallel")]
+        {
+            rayon::prelude::IntoParallelRefIterator::par_iter(self)
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            self.iter()
+        }
+    }
+}
+
+#[cfg(feature = "parallel")]
+pub type Lines<'a> = rayon::vec::IntoIter<&'a str>;
+#[cfg(not(feature = "parallel"))]
+pub type Lines<'a> = std::str::Lines<'a>;
+
+/// The lines of `input` like [`str::lines`], in parallel with the feature. Those have to be
+/// collected first so that every line knows where it is.
+pub fn maybe_par_lines(input: &str) -> Lines<'_> {
+    #[cfg(feature = "parallel")]
+    {
+        rayon::prelude::IntoParallelIterator::into_par_iter(input.lines().collect::<Vec<_>>())
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        input.lines()
+    }
+}
+
+/// Goes on from a [`MaybeParallel::maybe_par_iter`] as a plain iterator, in the original order,
+/// for what only plain iterators have. The parallel build has to collect for that, without the
+/// feature the iterator is plain already and nothing is allocated.
+pub trait Sequential: Sized {
+    type Iter: Iterator;
+
+    fn sequential(self) -> Self::Iter;
+}
+
+#[cfg(not(feature = "parallel"))]
+impl<I: Iterator> Sequential for I {
+    type Iter = Self;
+
+    fn sequential(self) -> Self::Iter {
+        self
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl<I: rayon::iter::ParallelIterator> Sequential for I {
+    type Iter = std::vec::IntoIter<I::Item>;
+
+    fn sequential(self) -> Self::Iter {
+        self.collect::<Vec<_>>().into_iter()
+    }
+}
@@ -0,0 +1,220 @@
+use std::str::FromStr;
+
+use color_eyre::eyre::{eyre, Result, WrapErr};
+use color_eyre::Report;
+use nom::bytes::complete::tag;
+use nom::character::complete::{char, digit1, multispace0, one_of, space0, space1};
+use nom::combinator::{all_consuming, map_res, opt, recognize};
+use nom::multi::separated_list1;
+use nom::sequence::{pair, preceded, separated_pair, terminated, tuple};
+use nom::{Finish, IResult, Parser};
+
+use crate::parallel::prelude::*;
+
+/// Runs `parser` over the whole of `input` (trailing whitespace allowed) and turns a nom
+/// failure into an error that points at the offending line.
+pub fn parse_all<'a, O>(
+    input: &'a str,
+    parser: impl Parser<&'a str, O, nom::error::Error<&'a str>>,
+) -> Result<O> {
+    all_consuming(terminated(parser, multispace0))(input)
+        .finish()
+        .map(|(_, output)| output)
+        .map_err(|e| {
+            let offset = input.len() - e.input.len();
+            let line = input[..offset].matches('\n').count() + 1;
+            let snippet = e.input.lines().next().unwrap_or_default();
+            eyre!("parse error ({:?}) on line {}: {:?}", e.code, line, snippet)
+        })
+}
+
+/// Parses every non-empty line of `input` with `T::from_str`, in parallel with the `parallel`
+/// feature. The error is always the one of the first bad line.
+pub fn parse_lines<T: FromStr<Err = Report> + Send>(input: &str) -> Result<Vec<T>> {
+    maybe_par_lines(input)
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| l.parse().wrap_err_with(|| format!("line {}", i + 1)))
+        .sequential()
+        .collect()
+}
+
+/// Splits `input` into blocks separated by one or more blank lines.
+pub fn blocks(input: &str) -> Vec<&str> {
+    let mut blocks = Vec::new();
+    let mut start = None;
+    let mut end = 0;
+    let mut offset = 0;
+
+    for line in input.split_inclusive('\n') {
+        if line.trim().is_empty() {
+            if let Some(s) = start.take() {
+                blocks.push(input[s..end].trim_end());
+            }
+        } else {
+            start.get_or_insert(offset);
+            end = offset + line.len();
+        }
+        offset += line.len();
+    }
+    if let Some(s) = start {
+        blocks.push(input[s..end].trim_end());
+    }
+
+    blocks
+}
+
+pub fn unsigned<T: FromStr>(i: &str) -> IResult<&str, T> {
+    map_res(digit1, str::parse)(i)
+}
+
+pub fn signed<T: FromStr>(i: &str) -> IResult<&str, T> {
+    map_res(recognize(pair(opt(one_of("+-")), digit1)), str::parse)(i)
+}
+
+/// Whitespace separated unsigned integers, leading whitespace is skipped.
+pub fn unsigned_list<T: FromStr>(i: &str) -> IResult<&str, Vec<T>> {
+    preceded(space0, separated_list1(space1, unsigned))(i)
+}
+
+/// Whitespace separated integers with an optional sign, leading whitespace is skipped.
+pub fn signed_list<T: FromStr>(i: &str) -> IResult<&str, Vec<T>> {
+    preceded(space0, separated_list1(space1, signed))(i)
+}
+
+/// Whitespace separated runs of digits, kept as text so they can be concatenated.
+pub fn digit_words(i: &str) -> IResult<&str, Vec<&str>> {
+    preceded(space0, separated_list1(space1, digit1))(i)
+}
+
+/// Matches `label` followed by optional spaces, e.g. `"seeds:"` in `"seeds: 79 14"`.
+pub fn labelled<'a, O>(
+    label: &'static str,
+    parser: impl Parser<&'a str, O, nom::error::Error<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O> {
+    preceded(tuple((tag(label), space0)), parser)
+}
+
+/// A single `key separator value` pair, e.g. `"3 blue"` or `"AAA = (BBB, CCC)"`.
+pub fn key_value<'a, K, V>(
+    key: impl Parser<&'a str, K, nom::error::Error<&'a str>>,
+    separator: &'static str,
+    value: impl Parser<&'a str, V, nom::error::Error<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (K, V)> {
+    separated_pair(key, tag(separator), value)
+}
+
+/// One or more `item`s separated by `delimiter`, whitespace around the delimiter is ignored.
+pub fn records<'a, O>(
+    item: impl Parser<&'a str, O, nom::error::Error<&'a str>>,
+    delimiter: char,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    preceded(
+        space0,
+        separated_list1(tuple((space0, char(delimiter), space0)), item),
+    )
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Grid {
+    pub cells: Vec<char>,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// Reads a rectangular block of characters, blank lines are ignored.
+pub fn char_grid(input: &str) -> Result<Grid> {
+    let mut cells = Vec::new();
+    let mut width = None;
+    let mut height = 0;
+
+    for (i, line) in input.lines().map(str::trim).enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+        let len = line.chars().count();
+        match width {
+            None => width = Some(len),
+            Some(w) if w != len => {
+                return Err(eyre!(
+                    "line {} has width {} but the grid is {} wide",
+                    i + 1,
+                    len,
+                    w
+                ))
+            }
+            Some(_) => {}
+        }
+        cells.extend(line.chars());
+        height += 1;
+    }
+
+    let width = width.ok_or_else(|| eyre!("grid is empty"))?;
+    Ok(Grid {
+        cells,
+        width,
+        height,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn integer_lists() {
+        assert_eq!(
+            parse_all("  1 22   333", unsigned_list::<u32>).unwrap(),
+            vec![1, 22, 333]
+        );
+        assert_eq!(
+            parse_all("-1 +2 3", signed_list::<i64>).unwrap(),
+            vec![-1, 2, 3]
+        );
+        assert!(parse_all("1 -2", unsigned_list::<u32>).is_err());
+        assert!(parse_all("256", unsigned_list::<u8>).is_err());
+    }
+
+    #[test]
+    fn labelled_sections() {
+        assert_eq!(
+            parse_all("seeds: 79 14", labelled("seeds:", unsigned_list::<u64>)).unwrap(),
+            vec![79, 14]
+        );
+        assert_eq!(
+            parse_all("Time:      7  15", labelled("Time:", digit_words)).unwrap(),
+            vec!["7", "15"]
+        );
+        let err = parse_all("Tim: 7", labelled("Time:", digit_words)).unwrap_err();
+        assert_eq!(err.to_string(), "parse error (Tag) on line 1: \"Tim: 7\"");
+    }
+
+    #[test]
+    fn blank_line_blocks() {
+        assert_eq!(blocks("a\nb\n\n\nc\r\n\r\nd\n"), vec!["a\nb", "c", "d"]);
+        assert_eq!(blocks("\n\n"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn key_value_records() {
+        let parser = records(
+            key_value(unsigned::<u32>, " ", nom::character::complete::alpha1),
+            ',',
+        );
+        assert_eq!(
+            parse_all("3 blue, 4 red", parser).unwrap(),
+            vec![(3, "blue"), (4, "red")]
+        );
+    }
+
+    #[test]
+    fn grids() {
+        let grid = char_grid("ab\ncd\n\n").unwrap();
+        assert_eq!((grid.width, grid.height), (2, 2));
+        assert_eq!(grid.cells, vec!['a', 'b', 'c', 'd']);
+        assert!(char_grid("ab\nc").is_err());
+        assert!(char_grid("").is_err());
+    }
+}
@@ -0,0 +1,328 @@
+use std::cell::Cell;
+use std::fmt::{self, Display};
+use std::panic::{self, AssertUnwindSafe};
+use std::str::FromStr;
+use std::sync::Once;
+
+use color_eyre::eyre::{eyre, Result};
+use color_eyre::Report;
+
+use crate::day;
+
+/// A piece of an input. Concatenating all chunks gives the input back, `reduce` only ever
+/// drops the removable ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chunk<'a> {
+    pub text: &'a str,
+    pub removable: bool,
+}
+
+/// One chunk per line (newline included), removable when `removable` says so.
+pub fn lines_where<'a>(input: &'a str, removable: impl Fn(&str) -> bool) -> Vec<Chunk<'a>> {
+    input
+        .split_inclusive('\n')
+        .map(|text| Chunk {
+            text,
+            removable: removable(text),
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Parse,
+    Part1,
+    Part2,
+}
+
+impl Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse => write!(f, "parsing"),
+            Self::Part1 => write!(f, "part 1"),
+            Self::Part2 => write!(f, "part 2"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure {
+    Panic(Stage),
+    Error(Stage, String),
+    Mismatch {
+        stage: Stage,
+        ours: String,
+        reference: String,
+    },
+}
+
+impl Failure {
+    pub const fn check(&self) -> Check {
+        match self {
+            Self::Panic(_) => Check::Panic,
+            Self::Error(..) => Check::Error,
+            Self::Mismatch { .. } => Check::Mismatch,
+        }
+    }
+
+    /// Whether `other` is still the same bug. Errors have to agree on the message, except
+    /// for numbers in it since those tend to be line numbers that move as lines are dropped.
+    fn is_like(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Panic(a), Self::Panic(b)) => a == b,
+            (Self::Error(a, m), Self::Error(b, n)) => {
+                a == b && without_numbers(m) == without_numbers(n)
+            }
+            (Self::Mismatch { stage: a, .. }, Self::Mismatch { stage: b, .. }) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Panic(stage) => write!(f, "panic in {}", stage),
+            Self::Error(stage, message) => write!(f, "error in {}: {}", stage, message),
+            Self::Mismatch {
+                stage,
+                ours,
+                reference,
+            } => write!(
+                f,
+                "{} answered {} but the reference says {}",
+                stage, ours, reference
+            ),
+        }
+    }
+}
+
+fn without_numbers(s: &str) -> String {
+    s.chars().filter(|c| !c.is_ascii_digit()).collect()
+}
+
+/// The kind of failure to preserve while reducing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    Panic,
+    Error,
+    Mismatch,
+}
+
+impl FromStr for Check {
+    type Err = Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "panic" => Ok(Self::Panic),
+            "error" => Ok(Self::Error),
+            "mismatch" => Ok(Self::Mismatch),
+            _ => Err(eyre!("expected panic, error or mismatch, got {:?}", s)),
+        }
+    }
+}
+
+fn attempt<T>(stage: Stage, f: impl FnOnce() -> Result<T>) -> Result<T, Failure> {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Err(_) => Err(Failure::Panic(stage)),
+        Ok(Err(e)) => Err(Failure::Error(stage, e.root_cause().to_string())),
+        Ok(Ok(value)) => Ok(value),
+    }
+}
+
+/// Runs day `number` on `input` and reports the first thing that goes wrong. Answers are only
+/// compared with the reference solver when both parts succeed, and a reference that panics
+/// counts as no reference at all. The reference is brute force and can take hours on a large
+/// input, so with `looking_for` set to anything but a mismatch it isn't run.
+pub fn failure(number: u32, input: &str, looking_for: Option<Check>) -> Result<Option<Failure>> {
+    let mut day = day(number)?;
+    let answers = attempt(Stage::Parse, || day.parse_input(input)).and_then(|()| {
+        Ok((
+            attempt(Stage::Part1, || day.part_1())?,
+            attempt(Stage::Part2, || day.part_2())?,
+        ))
+    });
+    let (part_1, part_2) = match answers {
+        Ok(answers) => answers,
+        Err(failure) => return Ok(Some(failure)),
+    };
+    if looking_for.is_some_and(|check| check != Check::Mismatch) {
+        return Ok(None);
+    }
+
+    let Ok((reference_1, reference_2)) =
+        panic::catch_unwind(AssertUnwindSafe(|| day.reference_answers(input)))
+    else {
+        return Ok(None);
+    };
+    for (stage, ours, reference) in [
+        (Stage::Part1, part_1, reference_1),
+        (Stage::Part2, part_2, reference_2),
+    ] {
+        if let (Some(ours), Some(reference)) = (ours, reference) {
+            if ours != reference {
+                return Ok(Some(Failure::Mismatch {
+                    stage,
+                    ours,
+                    reference,
+                }));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Drops removable chunks for as long as `still_fails` holds, first in large runs and then
+/// one at a time, and returns the smallest input found.
+pub fn minimize(chunks: &[Chunk], mut still_fails: impl FnMut(&str) -> bool) -> String {
+    let render = |keep: &[bool]| {
+        chunks
+            .iter()
+            .zip(keep)
+            .filter(|(_, &k)| k)
+            .map(|(c, _)| c.text)
+            .collect::<String>()
+    };
+
+    let mut keep = vec![true; chunks.len()];
+    let mut run = chunks.iter().filter(|c| c.removable).count().div_ceil(2);
+    while run > 0 {
+        let alive = (0..chunks.len())
+            .filter(|&i| keep[i] && chunks[i].removable)
+            .collect::<Vec<_>>();
+        let mut progress = false;
+        for window in alive.chunks(run) {
+            window.iter().for_each(|&i| keep[i] = false);
+            if still_fails(&render(&keep)) {
+                progress = true;
+            } else {
+                window.iter().for_each(|&i| keep[i] = true);
+            }
+        }
+        // Keep going at the same size while it pays off, a run of one only stops once a
+        // whole pass removed nothing.
+        if !progress {
+            run /= 2;
+        }
+    }
+
+    render(&keep)
+}
+
+/// Minimizes `input` for day `number` while it keeps failing like it does now. With `check`
+/// the input has to fail in that way to begin with.
+pub fn reduce(number: u32, input: &str, check: Option<Check>) -> Result<(String, Failure)> {
+    let original = failure(number, input, None)?.ok_or_else(|| eyre!("the input does not fail"))?;
+    if let Some(check) = check {
+        if original.check() != check {
+            return Err(eyre!("expected a {:?} but got: {}", check, original));
+        }
+    }
+
+    let chunks = day(number)?.reduction_chunks(input);
+    // Panics are expected from here on, the default hook would print every one of them.
+    let silenced = Silenced::new();
+    let looking_for = Some(original.check());
+    let reduced = minimize(
+        &chunks,
+        |candidate| matches!(failure(number, candidate, looking_for), Ok(Some(f)) if f.is_like(&original)),
+    );
+    drop(silenced);
+
+    Ok((reduced, original))
+}
+
+thread_local! {
+    /// Whether panics on this thread go unreported.
+    static SILENCED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Keeps panics on this thread unreported for as long as it lives, even if it is dropped
+/// while unwinding.
+struct Silenced;
+
+impl Silenced {
+    fn new() -> Self {
+        silence_panics();
+        SILENCED.set(true);
+        Self
+    }
+}
+
+impl Drop for Silenced {
+    fn drop(&mut self) {
+        SILENCED.set(false);
+    }
+}
+
+/// Wraps the panic hook, once for the whole process, in one that says nothing on threads
+/// that are [`SILENCED`]. Swapping the hook for every reduction would race with any other
+/// thread doing the same. Panics on the worker threads of the `parallel` feature are not
+/// silenced.
+fn silence_panics() {
+    static WRAP: Once = Once::new();
+    WRAP.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !SILENCED.get() {
+                hook(info);
+            }
+        }));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn keeps_what_the_predicate_needs() {
+        let input = "a\nb\nc\nd\ne\nf\ng\n";
+        let chunks = lines_where(input, |l| l != "a\n");
+        let reduced = minimize(&chunks, |s| s.contains('c') && s.contains('f'));
+        assert_eq!(reduced, "a\nc\nf\n");
+    }
+
+    #[test]
+    fn day01_line_without_digits() {
+        let input = "1abc2\npqr3stu8vwx\nabc\na1b2c3d4e5f\ntreb7uchet\n";
+        let (reduced, failure) = reduce(1, input, Some(Check::Error)).unwrap();
+        assert_eq!(reduced, "abc\n");
+        assert_eq!(
+            failure,
+            Failure::Error(Stage::Part1, "line 3 has no digits".into())
+        );
+
+        assert!(reduce(1, "1abc2\n", None).is_err());
+        assert!(reduce(1, input, Some(Check::Panic)).is_err());
+    }
+
+    #[test]
+    fn reference_only_runs_for_mismatches() {
+        // The reference would walk through two billion seeds.
+        let input = "seeds: 1000000000 2000000000\n\nseed-to-location map:\n1 2 3\n";
+        assert_eq!(failure(5, input, Some(Check::Error)).unwrap(), None);
+        assert_eq!(failure(5, input, Some(Check::Panic)).unwrap(), None);
+    }
+
+    #[test]
+    fn panics_are_reported_again_after_an_unwind() {
+        let unwound = panic::catch_unwind(|| {
+            let _silenced = Silenced::new();
+            panic!("while reducing");
+        });
+        assert!(unwound.is_err());
+        assert!(!SILENCED.get());
+    }
+
+    #[test]
+    fn day08_keeps_the_path() {
+        let input =
+            "LR\n\nAAA = (BBB, CCC)\nBBB = (BBB, BBB)\nCCC = (DDD, DDD)\nZZZ = (ZZZ, ZZZ)\n";
+        let (reduced, _) = reduce(8, input, None).unwrap();
+        assert_eq!(reduced, "LR\n\nAAA = (BBB, CCC)\nBBB = (BBB, BBB)\n");
+    }
+}
@@ -0,0 +1,68 @@
+use std::io::BufRead;
+
+use color_eyre::eyre::{Result, WrapErr};
+
+/// Inputs at least this large are streamed when the day supports it.
+pub const STREAM_THRESHOLD: u64 = 64 * 1024 * 1024;
+
+/// A day's answers folded over its input one line at a time, so the input never has to be in
+/// memory as a whole. Lines come without their line ending, blank ones included.
+pub trait LineSolver {
+    fn feed(&mut self, line: &str) -> Result<()>;
+    fn finish(self: Box<Self>) -> Result<(Option<String>, Option<String>)>;
+}
+
+/// Feeds every line of `reader` to `solver`, reusing one buffer for all of them.
+pub fn solve_lines(
+    mut solver: Box<dyn LineSolver>,
+    mut reader: impl BufRead,
+) -> Result<(Option<String>, Option<String>)> {
+    let mut line = String::new();
+    let mut number = 0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        number += 1;
+        let trimmed = line.trim_end_matches(['\n', '\r']);
+        solver
+            .feed(trimmed)
+            .wrap_err_with(|| format!("line {}", number))?;
+    }
+
+    solver.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::day;
+    use crate::rng::Rng;
+
+    use super::*;
+
+    #[test]
+    fn streams_like_the_batch_solvers() {
+        for n in [1, 4, 7, 9] {
+            for seed in 0..20 {
+                let mut day = day(n).unwrap();
+                let input = day.generate_input(&mut Rng::new(seed), 200);
+                day.parse_input(&input).unwrap();
+                let batch = (day.part_1().unwrap(), day.part_2().unwrap());
+
+                let solver = day.line_solver().unwrap();
+                let streamed = solve_lines(solver, input.as_bytes()).unwrap();
+                assert_eq!(streamed, batch, "day {} seed {}", n, seed);
+            }
+        }
+    }
+
+    #[test]
+    fn errors_point_at_the_line() {
+        let solver = day(9).unwrap().line_solver().unwrap();
+        let err = solve_lines(solver, "1 2 3\r\n\n1 x\n".as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "line 3");
+    }
+}